@@ -1,4 +1,4 @@
-use advent_of_code2023::coordinate::{IndexingCoordinate, PosCoordinate};
+use advent_of_code2023::coordinate::{BoundingBox, Coordinate, IndexingCoordinate, PosCoordinate};
 use color_eyre::Result;
 use itertools::Itertools;
 use std::time::Instant;
@@ -45,30 +45,20 @@ impl Pipe {
         max_col: usize,
     ) -> Vec<IndexingCoordinate> {
         // Change type to make going out of bounds temporarily safe
-        let (row, col) = (coordinate.row as i32, coordinate.col as i32);
-        let max_r = max_row as i32;
-        let max_c = max_col as i32;
+        let position =
+            Coordinate::<isize>::try_from(*coordinate).expect("Map should fit in an isize");
         match self {
-            Pipe::Vertical => vec![(row - 1, col), (row + 1, col)],
-            Pipe::Horizontal => vec![(row, col - 1), (row, col + 1)],
-            Pipe::NE => vec![(row - 1, col), (row, col + 1)],
-            Pipe::NW => vec![(row - 1, col), (row, col - 1)],
-            Pipe::SE => vec![(row + 1, col), (row, col + 1)],
-            Pipe::SW => vec![(row + 1, col), (row, col - 1)],
+            Pipe::Vertical => vec![(-1, 0), (1, 0)],
+            Pipe::Horizontal => vec![(0, -1), (0, 1)],
+            Pipe::NE => vec![(-1, 0), (0, 1)],
+            Pipe::NW => vec![(-1, 0), (0, -1)],
+            Pipe::SE => vec![(1, 0), (0, 1)],
+            Pipe::SW => vec![(1, 0), (0, -1)],
             Pipe::Ground => vec![],
             _ => panic!("Invalid pipe, {:?} cannot be connected", self),
         }
-        .iter()
-        .filter_map(|(r, c)| {
-            if *r < 0 || *r >= max_r || *c < 0 || *c >= max_c {
-                None
-            } else {
-                Some(IndexingCoordinate {
-                    row: *r as usize,
-                    col: *c as usize,
-                })
-            }
-        })
+        .into_iter()
+        .filter_map(|offset| (position + Coordinate::from(offset)).to_indexing(max_row, max_col))
         .collect_vec()
     }
 }
@@ -159,7 +149,7 @@ fn solve_part1(input: &Input) -> u32 {
     let start_type = identify_pipe(
         start,
         start
-            .get_adjacent_points(max_row, max_col)
+            .get_adjacent_points(&BoundingBox::from_size(max_row, max_col))
             .iter()
             .filter(|c| {
                 let p = c.get(&input.map);
@@ -1,7 +1,11 @@
-use advent_of_code2023::{coordinate::Coordinate, transpose};
+use advent_of_code2023::{
+    coordinate::{Coordinate, IndexingCoordinate},
+    matrix::Matrix,
+};
 use color_eyre::Result;
 use itertools::Itertools;
-use std::{time::Instant, vec};
+use num::PrimInt;
+use std::{num::TryFromIntError, time::Instant, vec};
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone, Copy)]
 pub enum Point {
@@ -65,11 +69,12 @@ fn find_empty_rows(input: &[Vec<Point>]) -> Vec<usize> {
 }
 
 fn find_empty_cols(input: &[Vec<Point>]) -> Vec<usize> {
-    transpose(input)
-        .iter()
+    Matrix::try_from(input)
+        .expect("Found invalid galaxy")
+        .view()
+        .iter_cols()
         .enumerate()
-        .filter(|(_, points)| points.iter().all(|p| *p == Point::Space))
-        .map(|(row, _)| row)
+        .filter_map(|(col, mut points)| points.all(|p| *p == Point::Space).then_some(col))
         .collect_vec()
 }
 
@@ -103,14 +108,21 @@ fn expand(input: &[Vec<Point>]) -> Vec<Vec<Point>> {
         .collect_vec()
 }
 
-fn get_galaxy_coordinates(expanded: &[Vec<Point>]) -> Vec<Coordinate<i32>> {
+fn get_galaxy_coordinates<T: PrimInt>(expanded: &[Vec<Point>]) -> Vec<Coordinate<T>>
+where
+    Coordinate<T>: TryFrom<IndexingCoordinate, Error = TryFromIntError>,
+{
     expanded
         .iter()
         .enumerate()
         .flat_map(|(row, points)| {
             points.iter().enumerate().filter_map(move |(col, p)| {
                 if *p == Point::Galaxy {
-                    Some((row as i32, col as i32).into())
+                    Some(
+                        IndexingCoordinate::new(row, col)
+                            .try_into()
+                            .expect("Galaxy should fit in the coordinate type"),
+                    )
                 } else {
                     None
                 }
@@ -124,7 +136,7 @@ fn solve_part1(input: &[Vec<Point>]) -> i32 {
     if !expanded.iter().map(|r| r.len()).all_equal() {
         panic!("Found invalid galaxy")
     }
-    let galaxy_coordinates = get_galaxy_coordinates(&expanded);
+    let galaxy_coordinates = get_galaxy_coordinates::<i32>(&expanded);
     galaxy_coordinates
         .iter()
         .tuple_combinations()
@@ -132,28 +144,29 @@ fn solve_part1(input: &[Vec<Point>]) -> i32 {
         .sum()
 }
 
-fn row_between(row: i32, a: &Coordinate<i32>, b: &Coordinate<i32>) -> bool {
+fn row_between<T: PrimInt>(row: T, a: &Coordinate<T>, b: &Coordinate<T>) -> bool {
     row > a.row && row < b.row || row > b.row && row < a.row
 }
 
-fn col_between(col: i32, a: &Coordinate<i32>, b: &Coordinate<i32>) -> bool {
+fn col_between<T: PrimInt>(col: T, a: &Coordinate<T>, b: &Coordinate<T>) -> bool {
     col > a.col && col < b.col || col > b.col && col < a.col
 }
 
 fn solve_part2(input: &[Vec<Point>], expansion: u64) -> u64 {
-    let empty_rows = find_empty_rows(input);
-    let empty_cols = find_empty_cols(input);
+    let to_u32 = |i: usize| u32::try_from(i).expect("Empty line should fit in a u32");
+    let empty_rows = find_empty_rows(input).into_iter().map(to_u32).collect_vec();
+    let empty_cols = find_empty_cols(input).into_iter().map(to_u32).collect_vec();
     // When calculating manhattan distance, add expansion * the rows and cols between the start and end
-    let galaxy_coordinates = get_galaxy_coordinates(input);
+    let galaxy_coordinates = get_galaxy_coordinates::<u32>(input);
     galaxy_coordinates
         .iter()
         .tuple_combinations()
         .map(|(a, b)| {
-            let base = a.manhattan_distance(b) as u64;
+            let base = a.manhattan_distance_as::<u64>(b);
             let empty_crossed = empty_cols
                 .iter()
-                .filter(|&c| col_between(*c as i32, a, b))
-                .chain(empty_rows.iter().filter(|&r| row_between(*r as i32, a, b)))
+                .filter(|&&c| col_between(c, a, b))
+                .chain(empty_rows.iter().filter(|&&r| row_between(r, a, b)))
                 .count() as u64;
             // println!(
             //     "Distance from a: {:?} to b: {:?} is {}",
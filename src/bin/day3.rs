@@ -1,4 +1,4 @@
-use advent_of_code2023::coordinate::{Coordinate, IndexingCoordinate};
+use advent_of_code2023::coordinate::{BoundingBox, Coordinate, IndexingCoordinate};
 use color_eyre::Result;
 use itertools::Itertools;
 use std::time::Instant;
@@ -93,11 +93,10 @@ mod parsing {
 fn get_adjacent_parts(
     coord: IndexingCoordinate,
     input: &[Vec<Element>],
-    max_row: usize,
-    max_col: usize,
+    bounds: &BoundingBox<usize>,
 ) -> Vec<IndexingCoordinate> {
     coord
-        .get_adjacent_points_diagonal(max_row, max_col)
+        .get_adjacent_points_diagonal(bounds)
         .iter()
         .filter_map(|adj| {
             let other = adj.get(input);
@@ -118,8 +117,7 @@ fn get_adjacent_parts(
 }
 
 fn solve_part1(input: &Vec<Vec<Element>>) -> u32 {
-    let max_row = input.len();
-    let max_col = input[0].len();
+    let bounds = BoundingBox::from_size(input.len(), input[0].len());
     let parts = input
         .iter()
         .enumerate()
@@ -130,7 +128,7 @@ fn solve_part1(input: &Vec<Vec<Element>>) -> u32 {
                 .filter_map(|(col, element)| {
                     if let Element::Symbol(_) = element {
                         let coord = IndexingCoordinate::new(row, col);
-                        return Some(get_adjacent_parts(coord, input, max_row, max_col));
+                        return Some(get_adjacent_parts(coord, input, &bounds));
                     }
                     None
                 })
@@ -149,8 +147,7 @@ fn solve_part1(input: &Vec<Vec<Element>>) -> u32 {
 }
 
 fn solve_part2(input: &Vec<Vec<Element>>) -> u32 {
-    let max_row = input.len();
-    let max_col = input[0].len();
+    let bounds = BoundingBox::from_size(input.len(), input[0].len());
     input
         .iter()
         .enumerate()
@@ -161,7 +158,7 @@ fn solve_part2(input: &Vec<Vec<Element>>) -> u32 {
                 .filter_map(|(col, element)| {
                     if let Element::Symbol('*') = element {
                         let coord = IndexingCoordinate::new(row, col);
-                        let adj_parts = get_adjacent_parts(coord, input, max_row, max_col);
+                        let adj_parts = get_adjacent_parts(coord, input, &bounds);
                         if adj_parts.len() == 2 {
                             return match (adj_parts[0].get(input), adj_parts[1].get(input)) {
                                 (Element::PartHead(a), Element::PartHead(b)) => Some(a * b),
@@ -197,4 +194,14 @@ mod tests {
         assert_eq!(result, 467835);
         Ok(())
     }
+
+    #[test]
+    fn test_plus_symbol_before_number() -> color_eyre::Result<()> {
+        // The + is a symbol, not the sign of 35
+        let input = parsing::parse_input("+35..\n...*2\n")?;
+        assert_eq!(input[0].len(), 5);
+        assert_eq!(solve_part1(&input), 37);
+        assert_eq!(solve_part2(&input), 70);
+        Ok(())
+    }
 }
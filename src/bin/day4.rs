@@ -29,29 +29,18 @@ fn main() -> Result<()> {
 mod parsing {
 
     use super::Input;
+    use advent_of_code2023::parsing::{aligned_numbers, label};
     use color_eyre::Result;
-    use nom::bytes::complete::{take, take_until};
-    use nom::combinator::map_opt;
     use nom::error::Error;
-    use nom::multi::separated_list1;
-    use nom::sequence::{pair, preceded, separated_pair};
+    use nom::sequence::{preceded, separated_pair};
     use nom::{bytes::complete::tag, combinator::map, Finish, IResult};
 
-    fn parse_number(input: &str) -> IResult<&str, u32> {
-        // each number is always 2 digits
-        map_opt(take(2usize), |x: &str| x.trim().parse::<u32>().ok())(input)
-    }
-
-    // This parse_numbers is slightly different because of the number formatting for day 4
-    fn parse_numbers(input: &str) -> IResult<&str, Vec<u32>> {
-        separated_list1(tag(" "), parse_number)(input)
-    }
-
     fn parse_line(input: &str) -> IResult<&str, Input> {
         map(
             preceded(
-                pair(take_until(": "), tag(": ")),
-                separated_pair(parse_numbers, tag(" | "), parse_numbers),
+                label,
+                // each number is always 2 digits
+                separated_pair(aligned_numbers(2), tag(" | "), aligned_numbers(2)),
             ),
             |(winning, our_numbers)| Input {
                 winning,
@@ -49,16 +49,18 @@ mod parsing {
     use crate::MappingRow;
 
     use super::Input;
-    use advent_of_code2023::parsing::{parse_number, parse_numbers};
+    use advent_of_code2023::parsing::{
+        blank_lines, blocks, label, labelled, parse_number, parse_numbers,
+    };
     use color_eyre::Result;
-    use nom::bytes::complete::take_till;
+    use nom::character::complete::line_ending;
     use nom::error::Error;
     use nom::multi::separated_list1;
-    use nom::sequence::{pair, preceded, separated_pair, terminated, tuple};
+    use nom::sequence::{preceded, separated_pair, terminated, tuple};
     use nom::{bytes::complete::tag, combinator::map, Finish, IResult};
 
     fn parse_seeds(input: &str) -> IResult<&str, Vec<u64>> {
-        preceded(tag("seeds: "), parse_numbers)(input)
+        labelled("seeds", parse_numbers)(input)
     }
 
     fn parse_mapping_row(input: &str) -> IResult<&str, MappingRow> {
@@ -77,20 +79,13 @@ mod parsing {
     }
 
     fn parse_mapping(input: &str) -> IResult<&str, Vec<MappingRow>> {
-        // skip a line, then read mappings
-        preceded(
-            pair(take_till(|c| c == '\n'), tag("\n")),
-            separated_list1(tag("\n"), parse_mapping_row),
-        )(input)
+        // skip the map name, then read mappings
+        preceded(label, separated_list1(line_ending, parse_mapping_row))(input)
     }
 
     pub fn parse_input(input: &str) -> Result<Input, Error<&str>> {
         map(
-            separated_pair(
-                parse_seeds,
-                tag("\n\n"),
-                separated_list1(tag("\n\n"), parse_mapping),
-            ),
+            separated_pair(parse_seeds, blank_lines, blocks(parse_mapping)),
             |(seeds, mappings)| Input { seeds, mappings },
         )(input)
         .finish()
@@ -36,26 +36,29 @@ mod parsing {
     use crate::Race;
 
     use super::Input;
-    use advent_of_code2023::parsing::parse_numbers;
+    use advent_of_code2023::parsing::{labelled, padded_numbers};
     use color_eyre::Result;
     use itertools::Itertools;
-    use nom::bytes::complete::take_till;
+    use nom::character::complete::line_ending;
     use nom::error::Error;
-    use nom::sequence::{pair, preceded};
+    use nom::sequence::separated_pair;
     use nom::{combinator::map, Finish, IResult};
 
-    fn parse_line(input: &str) -> IResult<&str, Vec<i64>> {
-        preceded(take_till(|c: char| c.is_ascii_digit()), parse_numbers)(input)
+    fn parse_line<'a>(name: &'a str) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<i64>> {
+        labelled(name, padded_numbers)
     }
 
     pub fn parse_input(input: &str) -> Result<Input, Error<&str>> {
-        map(pair(parse_line, parse_line), |(times, distances)| Input {
-            races: times
-                .iter()
-                .zip(distances.iter())
-                .map(|(&time, &distance)| Race { time, distance })
-                .collect_vec(),
-        })(input)
+        map(
+            separated_pair(parse_line("Time"), line_ending, parse_line("Distance")),
+            |(times, distances)| Input {
+                races: times
+                    .iter()
+                    .zip(distances.iter())
+                    .map(|(&time, &distance)| Race { time, distance })
+                    .collect_vec(),
+            },
+        )(input)
         .finish()
         .map(|r| r.1)
     }
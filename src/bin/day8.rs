@@ -1,28 +1,118 @@
+use advent_of_code2023::cycle::{self, Cycle};
+use advent_of_code2023::math;
+use advent_of_code2023::simulate::Simulation;
 use color_eyre::Result;
 use itertools::Itertools;
-use std::{collections::HashMap, time::Instant};
+use petgraph::dot::Dot;
+use petgraph::graph::{DiGraph, NodeIndex};
+use std::fmt::{self, Display};
+use std::{collections::HashMap, env, fs, iter, time::Instant};
 
 #[derive(Debug, Clone)]
 pub struct Input {
     directions: Vec<Direction>,
-    nodes: HashMap<String, Node>,
+    network: Network,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Direction {
     L,
     R,
 }
 
+impl Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?}", self)
+    }
+}
+
+type NodeId = u16;
+
+/// The nodes, with each name interned to the index of its node in the graph,
+/// so walking the network is just indexing into `left` or `right`.
+/// The graph keeps petgraph's default index, since it has twice as many edges as nodes
 #[derive(Debug, Clone)]
-pub struct Node {
-    left: String,
-    right: String,
+pub struct Network {
+    graph: DiGraph<String, Direction>,
+    ids: HashMap<String, NodeId>,
+    left: Vec<NodeId>,
+    right: Vec<NodeId>,
+    // Whether each node ends in Z
+    is_end: Vec<bool>,
+}
+
+impl Network {
+    /// Build from (name, left, right) triples. None if an edge goes to a node which isn't
+    /// listed, or there are too many nodes for a `NodeId`
+    fn new(nodes: &[(&str, &str, &str)]) -> Option<Network> {
+        NodeId::try_from(nodes.len()).ok()?;
+        let mut graph = DiGraph::with_capacity(nodes.len(), nodes.len() * 2);
+        let ids: HashMap<String, NodeId> = nodes
+            .iter()
+            .map(|(name, _, _)| {
+                (
+                    name.to_string(),
+                    graph.add_node(name.to_string()).index() as NodeId,
+                )
+            })
+            .collect();
+        let (mut left, mut right) = (vec![], vec![]);
+        for (name, l, r) in nodes {
+            let (from, l, r) = (ids[*name], *ids.get(*l)?, *ids.get(*r)?);
+            graph.add_edge(
+                NodeIndex::new(from.into()),
+                NodeIndex::new(l.into()),
+                Direction::L,
+            );
+            graph.add_edge(
+                NodeIndex::new(from.into()),
+                NodeIndex::new(r.into()),
+                Direction::R,
+            );
+            left.push(l);
+            right.push(r);
+        }
+        let is_end = nodes
+            .iter()
+            .map(|(name, _, _)| name.ends_with('Z'))
+            .collect_vec();
+        Some(Network {
+            graph,
+            ids,
+            left,
+            right,
+            is_end,
+        })
+    }
+
+    fn id(&self, name: &str) -> NodeId {
+        self.ids[name]
+    }
+
+    fn next(&self, id: NodeId, direction: Direction) -> NodeId {
+        match direction {
+            Direction::L => self.left[usize::from(id)],
+            Direction::R => self.right[usize::from(id)],
+        }
+    }
+
+    fn is_end(&self, id: NodeId) -> bool {
+        self.is_end[usize::from(id)]
+    }
+
+    /// Graphviz DOT for visualising the ghost loops, e.g. with `dot -Tsvg`
+    fn to_dot(&self) -> String {
+        Dot::new(&self.graph).to_string()
+    }
 }
 
 #[allow(dead_code)]
 fn main() -> Result<()> {
     let input = parsing::parse_input(include_str!("../../input/day8.txt"))?;
+    // Set DAY8_DOT to write out the network for graphviz
+    if env::var_os("DAY8_DOT").is_some() {
+        fs::write("day8.dot", input.network.to_dot())?;
+    }
     let time = Instant::now();
     println!(
         "Part 1: {} in {}ms",
@@ -40,18 +130,18 @@ fn main() -> Result<()> {
 
 mod parsing {
 
-    use std::collections::HashMap;
-
-    use crate::{Direction, Node};
+    use crate::{Direction, Network};
 
     use super::Input;
+    use advent_of_code2023::parsing::blank_lines;
     use color_eyre::Result;
     use nom::branch::alt;
     use nom::bytes::complete::take;
-    use nom::character::complete::char;
+    use nom::character::complete::{char, line_ending, multispace0};
+    use nom::combinator::map_opt;
     use nom::error::Error;
     use nom::multi::{many1, separated_list1};
-    use nom::sequence::{delimited, separated_pair};
+    use nom::sequence::{delimited, separated_pair, terminated};
     use nom::{bytes::complete::tag, combinator::map, Finish, IResult};
 
     fn parse_directions(input: &str) -> IResult<&str, Vec<Direction>> {
@@ -62,7 +152,7 @@ mod parsing {
         }))(input)
     }
 
-    fn parse_node(input: &str) -> IResult<&str, (String, Node)> {
+    fn parse_node(input: &str) -> IResult<&str, (&str, &str, &str)> {
         map(
             separated_pair(
                 take(3usize),
@@ -73,28 +163,27 @@ mod parsing {
                     char(')'),
                 ),
             ),
-            |(name, (left, right)): (&str, (&str, &str))| {
-                (
-                    name.to_string(),
-                    Node {
-                        left: left.to_string(),
-                        right: right.to_string(),
-                    },
-                )
-            },
+            |(name, (left, right))| (name, left, right),
         )(input)
     }
 
-    fn parse_nodes(input: &str) -> IResult<&str, HashMap<String, Node>> {
-        map(separated_list1(tag("\n"), parse_node), |pairs| {
-            pairs.into_iter().collect()
+    fn parse_network(input: &str) -> IResult<&str, Network> {
+        map_opt(separated_list1(line_ending, parse_node), |nodes| {
+            Network::new(&nodes)
         })(input)
     }
 
     pub fn parse_input(input: &str) -> Result<Input, Error<&str>> {
         map(
-            separated_pair(parse_directions, tag("\n\n"), parse_nodes),
-            |(directions, nodes)| Input { directions, nodes },
+            separated_pair(
+                parse_directions,
+                blank_lines,
+                terminated(parse_network, multispace0),
+            ),
+            |(directions, network)| Input {
+                directions,
+                network,
+            },
         )(input)
         .finish()
         .map(|r| r.1)
@@ -102,51 +191,100 @@ mod parsing {
 }
 
 fn solve_part1(input: &Input) -> u32 {
-    let mut current = "AAA";
-    let mut count = 0;
-    for d in input.directions.iter().cycle() {
-        let choice = input.nodes.get(current).unwrap();
-        let next = match d {
-            Direction::L => &choice.left,
-            Direction::R => &choice.right,
-        };
-        count += 1;
-        if next == "ZZZ" {
-            break;
-        }
-        current = next;
+    let end = input.network.id("ZZZ");
+    Simulation::new(&input.directions)
+        .repeating()
+        .run(
+            input.network.id("AAA"),
+            |&node, &d| input.network.next(node, d),
+            |&node| node == end,
+        )
+        .expect("Repeating directions never run out")
+        .steps as u32
+}
+
+// A ghost's position is the node plus how far through the directions it is,
+// since it only repeats once both repeat
+type State = (NodeId, usize);
+
+fn step(input: &Input, &(node, index): &State) -> State {
+    (
+        input.network.next(node, input.directions[index]),
+        (index + 1) % input.directions.len(),
+    )
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct Ghost {
+    cycle: Cycle,
+    // Every step up to the end of the first loop where the ghost is on a Z node
+    z_steps: Vec<usize>,
+}
+
+impl Ghost {
+    // The assumption the puzzle is built around: each ghost's first Z is at the end of its
+    // first loop, and it's the only Z in the loop, so it's on a Z at every multiple of the
+    // cycle length. That Z also has to be inside the loop, or it's never seen again
+    fn z_only_at_cycle_length(&self) -> bool {
+        self.z_steps == [self.cycle.length] && self.cycle.length >= self.cycle.start
     }
-    count
 }
 
-fn solve_part2(input: &Input) -> u32 {
-    // To do this efficiently requires cycle detection.
-    // Find a number of steps at which each element goes from Z -> Z, then do LCM to find the result
-    // In the example, the first element after 'A' is the cycle start, but not sure if that can be assumed
-    let mut current = input
-        .nodes
-        .keys()
-        .filter(|name| name.ends_with('A'))
+fn analyse_ghost(input: &Input, start: NodeId) -> Ghost {
+    let cycle = cycle::find_repeat((start, 0), |state| step(input, state));
+    let z_steps = iter::successors(Some((start, 0)), |state| Some(step(input, state)))
+        .take(cycle.start + cycle.length)
+        .positions(|(node, _)| input.network.is_end(node))
         .collect_vec();
-    let mut count = 0;
-    for d in input.directions.iter().cycle() {
-        let next = current
+    Ghost { cycle, z_steps }
+}
+
+fn solve_part2(input: &Input) -> u64 {
+    let ghosts = input
+        .network
+        .ids
+        .iter()
+        .filter(|(name, _)| name.ends_with('A'))
+        .map(|(_, &start)| analyse_ghost(input, start))
+        .collect_vec();
+    if ghosts.iter().all(Ghost::z_only_at_cycle_length) {
+        return math::lcm_all(ghosts.iter().map(|ghost| ghost.cycle.length as u64));
+    }
+    // Otherwise, first check the steps before every ghost is looping
+    let looping = ghosts
+        .iter()
+        .map(|ghost| ghost.cycle.start)
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    if let Some(steps) = (1..looping).find(|&steps| {
+        ghosts
             .iter()
-            .map(|&n| {
-                let choice = input.nodes.get(n).unwrap();
-                match d {
-                    Direction::L => &choice.left,
-                    Direction::R => &choice.right,
-                }
-            })
-            .collect_vec();
-        count += 1;
-        if next.iter().all(|n| n.ends_with('Z')) {
-            break;
-        }
-        current = next;
+            .all(|ghost| ghost.z_steps.contains(&ghost.cycle.equivalent_step(steps)))
+    }) {
+        return steps as u64;
     }
-    count
+    // After that each ghost is on a Z when steps = z (mod length) for one of the Zs in its loop,
+    // so solve each combination of those with CRT
+    ghosts
+        .iter()
+        .map(|ghost| {
+            ghost
+                .z_steps
+                .iter()
+                .filter(|&&z| z >= ghost.cycle.start)
+                .map(|&z| (z as i128, ghost.cycle.length as i128))
+                .collect_vec()
+        })
+        .multi_cartesian_product()
+        .filter_map(|congruences| {
+            let (steps, modulus) = math::crt(congruences)?;
+            // Move the solution forward to when every ghost is looping
+            let behind = (looping as i128 - steps).max(0);
+            Some(steps + (behind + modulus - 1) / modulus * modulus)
+        })
+        .min()
+        .expect("The ghosts never all reach Z together") as u64
 }
 
 #[cfg(test)]
@@ -168,4 +306,77 @@ mod tests {
         assert_eq!(result, 6);
         Ok(())
     }
+
+    #[test]
+    fn test_ghost_cycles() -> color_eyre::Result<()> {
+        let input = parsing::parse_input(include_str!("../../input/day8.test2.txt"))?;
+        let first = analyse_ghost(&input, input.network.id("11A"));
+        assert_eq!(
+            first,
+            Ghost {
+                cycle: Cycle {
+                    start: 1,
+                    length: 2
+                },
+                z_steps: vec![2]
+            }
+        );
+        assert!(first.z_only_at_cycle_length());
+        // The second ghost loops through its nodes twice before the directions line up again
+        let second = analyse_ghost(&input, input.network.id("22A"));
+        assert_eq!(
+            second,
+            Ghost {
+                cycle: Cycle {
+                    start: 1,
+                    length: 6
+                },
+                z_steps: vec![3, 6]
+            }
+        );
+        assert!(!second.z_only_at_cycle_length());
+        Ok(())
+    }
+
+    #[test]
+    fn test_part2_before_looping() -> color_eyre::Result<()> {
+        // The first ghost is already looping when they meet at step 4, but the second isn't
+        let input = parsing::parse_input(
+            "L\n\n\
+            11A = (11B, XXX)\n11B = (11Z, XXX)\n11Z = (11B, XXX)\n\
+            22A = (22B, XXX)\n22B = (22C, XXX)\n22C = (22D, XXX)\n22D = (22Z, XXX)\n\
+            22Z = (22E, XXX)\n22E = (22F, XXX)\n22F = (22E, XXX)\n\
+            XXX = (XXX, XXX)\n",
+        )?;
+        let second = analyse_ghost(&input, input.network.id("22A"));
+        assert_eq!(
+            second.cycle,
+            Cycle {
+                start: 5,
+                length: 2
+            }
+        );
+        assert!(!second.z_only_at_cycle_length());
+        assert_eq!(solve_part2(&input), 4);
+        Ok(())
+    }
+
+    #[test]
+    fn test_network() -> color_eyre::Result<()> {
+        let input = parsing::parse_input(include_str!("../../input/day8.test.txt"))?;
+        let network = &input.network;
+        let aaa = network.id("AAA");
+        assert_eq!(network.next(aaa, Direction::R), network.id("BBB"));
+        assert!(network.is_end(network.id("ZZZ")));
+        let dot = network.to_dot();
+        assert!(dot.starts_with("digraph {"));
+        assert!(dot.contains("label = \"AAA\""));
+        assert_eq!(dot.matches(" -> ").count(), 2 * network.left.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_missing_node() {
+        assert!(parsing::parse_input("L\n\nAAA = (AAA, BBB)\n").is_err());
+    }
 }
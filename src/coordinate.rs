@@ -1,9 +1,17 @@
+use color_eyre::eyre::{eyre, Result};
 use itertools::Itertools;
-use num::{abs, range_inclusive, PrimInt};
+use num::{abs, range_inclusive, PrimInt, Signed};
 use std::cmp::{max, min};
-use std::ops::{Add, Sub};
+use std::fmt::{Debug, Display};
+use std::hash::Hash;
+use std::iter;
+use std::num::TryFromIntError;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
 
-#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq)]
+use crate::render::{render_coordinates, Rulers};
+
+// Ordering is row-major, i.e. reading order
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct Coordinate<T: PrimInt> {
     pub row: T,
     pub col: T,
@@ -17,6 +25,14 @@ impl IndexingCoordinate {
     pub fn get<V: Copy>(&self, matrix: &[Vec<V>]) -> V {
         matrix[self.row][self.col]
     }
+
+    /// Move by a signed offset, or None if that would go below zero or overflow
+    pub fn checked_add_signed(&self, offset: Coordinate<isize>) -> Option<IndexingCoordinate> {
+        Some(Coordinate {
+            row: self.row.checked_add_signed(offset.row)?,
+            col: self.col.checked_add_signed(offset.col)?,
+        })
+    }
 }
 
 impl<T: PrimInt> From<(T, T)> for Coordinate<T> {
@@ -25,10 +41,186 @@ impl<T: PrimInt> From<(T, T)> for Coordinate<T> {
     }
 }
 
-impl Coordinate<i32> {
-    pub fn manhattan_distance(&self, other: &Coordinate<i32>) -> i32 {
+// Fallible conversions between integer types, failing if either axis doesn't fit
+macro_rules! impl_coordinate_try_from {
+    ($from:ty => $($to:ty),*) => {
+        $(
+            impl TryFrom<Coordinate<$from>> for Coordinate<$to> {
+                type Error = TryFromIntError;
+
+                fn try_from(c: Coordinate<$from>) -> Result<Self, Self::Error> {
+                    Ok(Coordinate {
+                        row: c.row.try_into()?,
+                        col: c.col.try_into()?,
+                    })
+                }
+            }
+        )*
+    };
+}
+
+impl_coordinate_try_from!(usize => isize, u32, i32, u64, i64);
+impl_coordinate_try_from!(isize => usize, u32, i32, u64, i64);
+impl_coordinate_try_from!(u32 => usize, isize, i32, u64, i64);
+impl_coordinate_try_from!(i32 => usize, isize, u32, u64, i64);
+impl_coordinate_try_from!(u64 => usize, isize, u32, i32, i64);
+impl_coordinate_try_from!(i64 => usize, isize, u32, i32, u64);
+
+impl Coordinate<isize> {
+    /// Convert to an index into a grid of `rows` x `cols`, or None if it's outside the grid
+    pub fn to_indexing(&self, rows: usize, cols: usize) -> Option<IndexingCoordinate> {
+        let c = IndexingCoordinate::try_from(*self).ok()?;
+        (c.row < rows && c.col < cols).then_some(c)
+    }
+}
+
+/// |a - b|, without underflowing for unsigned types
+pub fn abs_diff<T: PrimInt>(a: T, b: T) -> T {
+    if a > b {
+        a - b
+    } else {
+        b - a
+    }
+}
+
+impl<T: PrimInt + Signed> Coordinate<T> {
+    pub fn manhattan_distance(&self, other: &Coordinate<T>) -> T {
         abs(other.col - self.col) + abs(other.row - self.row)
     }
+
+    pub fn chebyshev_distance(&self, other: &Coordinate<T>) -> T {
+        max(abs(other.col - self.col), abs(other.row - self.row))
+    }
+
+    pub fn squared_euclidean_distance(&self, other: &Coordinate<T>) -> T {
+        let (row, col) = (other.row - self.row, other.col - self.col);
+        row * row + col * col
+    }
+
+    /// Rasterise the line from this coordinate to `other` with Bresenham's algorithm, so each
+    /// step moves to one of the 8 neighbours. Unlike `get_between`, this works for any pair of
+    /// points, and always runs from `self` to `other`
+    pub fn bresenham(&self, other: &Coordinate<T>) -> impl Iterator<Item = Coordinate<T>> {
+        let end = *other;
+        let (col_distance, row_distance) = (abs(end.col - self.col), -abs(end.row - self.row));
+        let (col_step, row_step) = ((end.col - self.col).signum(), (end.row - self.row).signum());
+        let mut error = col_distance + row_distance;
+        let mut next = Some(*self);
+        iter::from_fn(move || {
+            let current = next?;
+            next = (current != end).then(|| {
+                let double_error = error + error;
+                let mut step = current;
+                if double_error >= row_distance {
+                    error = error + row_distance;
+                    step.col = step.col + col_step;
+                }
+                if double_error <= col_distance {
+                    error = error + col_distance;
+                    step.row = step.row + row_step;
+                }
+                step
+            });
+            Some(current)
+        })
+    }
+
+    /// Treat `bounds` as one tile of an infinitely repeating grid. Returns the matching
+    /// coordinate inside `bounds`, and which copy of the tile this coordinate is in,
+    /// where (0, 0) is `bounds` itself and (-1, 0) is the copy above it
+    pub fn wrap(&self, bounds: &BoundingBox<T>) -> (Coordinate<T>, Coordinate<T>) {
+        let (row, tile_row) = wrap_axis(self.row, bounds.min.row, bounds.height());
+        let (col, tile_col) = wrap_axis(self.col, bounds.min.col, bounds.width());
+        (
+            Coordinate::new(row, col),
+            Coordinate::new(tile_row, tile_col),
+        )
+    }
+
+    /// The 4 orthogonal neighbours on a grid which repeats `bounds` forever,
+    /// each as the wrapped coordinate and its tile, as returned by `wrap`
+    pub fn get_adjacent_points_wrapping(
+        &self,
+        bounds: &BoundingBox<T>,
+    ) -> Vec<(Coordinate<T>, Coordinate<T>)> {
+        self.wrapped_offsets(bounds, &ORTHOGONAL)
+    }
+
+    /// As `get_adjacent_points_wrapping`, but including diagonals
+    pub fn get_adjacent_points_diagonal_wrapping(
+        &self,
+        bounds: &BoundingBox<T>,
+    ) -> Vec<(Coordinate<T>, Coordinate<T>)> {
+        let mut adj = self.wrapped_offsets(bounds, &ORTHOGONAL);
+        adj.extend(self.wrapped_offsets(bounds, &DIAGONAL));
+        adj
+    }
+
+    fn wrapped_offsets(
+        &self,
+        bounds: &BoundingBox<T>,
+        offsets: &[(i8, i8)],
+    ) -> Vec<(Coordinate<T>, Coordinate<T>)> {
+        offsets
+            .iter()
+            .map(|&(row, col)| {
+                let offset = Coordinate::new(
+                    T::from(row).expect("Signed types fit an i8"),
+                    T::from(col).expect("Signed types fit an i8"),
+                );
+                (*self + offset).wrap(bounds)
+            })
+            .collect_vec()
+    }
+}
+
+const ORTHOGONAL: [(i8, i8); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+const DIAGONAL: [(i8, i8); 4] = [(-1, -1), (1, 1), (1, -1), (-1, 1)];
+
+// Euclidean division of value - min by size, so the remainder is always in 0..size
+fn wrap_axis<T: PrimInt + Signed>(value: T, min: T, size: T) -> (T, T) {
+    let offset = value - min;
+    let (mut tile, mut remainder) = (offset / size, offset % size);
+    if remainder < T::zero() {
+        remainder = remainder + size;
+        tile = tile - T::one();
+    }
+    (min + remainder, tile)
+}
+
+// Distances for any integer type, calculated in a caller chosen accumulator A.
+// Each axis is widened to A before subtracting, so the differences can't overflow,
+// but adding or squaring them still can, e.g. the squared distance across a whole i32 is 2^65
+impl<T: PrimInt> Coordinate<T> {
+    /// The absolute difference along each axis
+    pub fn abs_diff(&self, other: &Coordinate<T>) -> Coordinate<T> {
+        Coordinate {
+            row: abs_diff(self.row, other.row),
+            col: abs_diff(self.col, other.col),
+        }
+    }
+
+    fn abs_diff_as<A: PrimInt + From<T>>(&self, other: &Coordinate<T>) -> (A, A) {
+        (
+            abs_diff(self.row.into(), other.row.into()),
+            abs_diff(self.col.into(), other.col.into()),
+        )
+    }
+
+    pub fn manhattan_distance_as<A: PrimInt + From<T>>(&self, other: &Coordinate<T>) -> A {
+        let (row, col) = self.abs_diff_as::<A>(other);
+        row + col
+    }
+
+    pub fn chebyshev_distance_as<A: PrimInt + From<T>>(&self, other: &Coordinate<T>) -> A {
+        let (row, col) = self.abs_diff_as::<A>(other);
+        max(row, col)
+    }
+
+    pub fn squared_euclidean_distance_as<A: PrimInt + From<T>>(&self, other: &Coordinate<T>) -> A {
+        let (row, col) = self.abs_diff_as::<A>(other);
+        row * row + col * col
+    }
 }
 
 impl<T: PrimInt> Coordinate<T> {
@@ -36,21 +228,52 @@ impl<T: PrimInt> Coordinate<T> {
         Coordinate { row, col }
     }
 
-    pub fn get_between(&self, other: &Coordinate<T>) -> Vec<Coordinate<T>> {
-        if self.row == other.row {
-            range_inclusive(min(self.col, other.col), max(self.col, other.col))
-                .map(|col| Coordinate {
-                    row: other.row,
-                    col,
-                })
-                .collect_vec()
-        } else if self.col == other.col {
-            range_inclusive(min(self.row, other.row), max(self.row, other.row))
-                .map(|row| Coordinate { row, col: self.col })
-                .collect_vec()
-        } else {
-            panic!("Invalid coordinates passed, must form a straight line");
+    /// Every coordinate on the line between the two, including both ends, starting from the
+    /// lesser end. Only horizontal, vertical and 45 degree lines go through whole coordinates,
+    /// so anything else is an error. See `bresenham` for other lines
+    pub fn get_between(&self, other: &Coordinate<T>) -> Result<impl Iterator<Item = Coordinate<T>>>
+    where
+        T: Debug,
+    {
+        let (start, end) = (min(*self, *other), max(*self, *other));
+        // Ordering is row-major, so the row never decreases
+        let (rows, cols) = (end.row - start.row, abs_diff(start.col, end.col));
+        if !(rows.is_zero() || cols.is_zero() || rows == cols) {
+            return Err(eyre!(
+                "{:?} and {:?} must form a horizontal, vertical or diagonal line",
+                self,
+                other
+            ));
         }
+        let row_step = if rows.is_zero() { T::zero() } else { T::one() };
+        let col_step = if cols.is_zero() { T::zero() } else { T::one() };
+        let col_ascending = start.col <= end.col;
+        Ok(
+            range_inclusive(T::zero(), max(rows, cols)).map(move |i| Coordinate {
+                row: start.row + i * row_step,
+                col: if col_ascending {
+                    start.col + i * col_step
+                } else {
+                    start.col - i * col_step
+                },
+            }),
+        )
+    }
+
+    /// Add, or None if either axis would overflow
+    pub fn checked_add(&self, other: &Coordinate<T>) -> Option<Coordinate<T>> {
+        Some(Coordinate {
+            row: self.row.checked_add(&other.row)?,
+            col: self.col.checked_add(&other.col)?,
+        })
+    }
+
+    /// Subtract, or None if either axis would overflow or, for unsigned types, go below zero
+    pub fn checked_sub(&self, other: &Coordinate<T>) -> Option<Coordinate<T>> {
+        Some(Coordinate {
+            row: self.row.checked_sub(&other.row)?,
+            col: self.col.checked_sub(&other.col)?,
+        })
     }
 
     pub fn transpose(&self) -> Coordinate<T> {
@@ -60,30 +283,29 @@ impl<T: PrimInt> Coordinate<T> {
         }
     }
 
-    pub fn get_adjacent_points(&self, max_row: T, max_col: T) -> Vec<Coordinate<T>> {
+    /// The up to 4 orthogonal neighbours which are inside `bounds`
+    pub fn get_adjacent_points(&self, bounds: &BoundingBox<T>) -> Vec<Coordinate<T>> {
         let mut adj = vec![];
-        let min_row = T::min_value();
-        let min_col = T::min_value();
         let one = T::one();
-        if self.row != min_row {
+        if self.row > bounds.min.row {
             adj.push(Coordinate {
                 row: self.row - one,
                 col: self.col,
             });
         }
-        if self.row != max_row - one {
+        if self.row < bounds.max.row {
             adj.push(Coordinate {
                 row: self.row + one,
                 col: self.col,
             });
         }
-        if self.col != min_col {
+        if self.col > bounds.min.col {
             adj.push(Coordinate {
                 row: self.row,
                 col: self.col - one,
             });
         }
-        if self.col != max_col - one {
+        if self.col < bounds.max.col {
             adj.push(Coordinate {
                 row: self.row,
                 col: self.col + one,
@@ -92,30 +314,33 @@ impl<T: PrimInt> Coordinate<T> {
         adj
     }
 
-    pub fn get_adjacent_points_diagonal(&self, max_row: T, max_col: T) -> Vec<Coordinate<T>> {
-        let mut adj = self.get_adjacent_points(max_row, max_col);
-        let min_row = T::min_value();
-        let min_col = T::min_value();
+    /// The up to 8 neighbours, including diagonals, which are inside `bounds`
+    pub fn get_adjacent_points_diagonal(&self, bounds: &BoundingBox<T>) -> Vec<Coordinate<T>> {
+        let mut adj = self.get_adjacent_points(bounds);
         let one = T::one();
-        if self.row != min_row && self.col != min_col {
+        let up = self.row > bounds.min.row;
+        let down = self.row < bounds.max.row;
+        let left = self.col > bounds.min.col;
+        let right = self.col < bounds.max.col;
+        if up && left {
             adj.push(Coordinate {
                 row: self.row - one,
                 col: self.col - one,
             });
         }
-        if self.row != max_row - one && self.col != max_col - one {
+        if down && right {
             adj.push(Coordinate {
                 row: self.row + one,
                 col: self.col + one,
             });
         }
-        if self.col != min_col && self.row != max_row - one {
+        if down && left {
             adj.push(Coordinate {
                 row: self.row + one,
                 col: self.col - one,
             });
         }
-        if self.col != max_col - one && self.row != min_row {
+        if up && right {
             adj.push(Coordinate {
                 row: self.row - one,
                 col: self.col + one,
@@ -147,34 +372,165 @@ impl<T: PrimInt> Sub for Coordinate<T> {
     }
 }
 
-// Print the matrix of coordinates. Find the min/max row/col in the matrix.
-// Between those coordinates, print # if the coordinate is present and . if its absent
-pub fn print_coordinates<T: PrimInt>(matrix: &[Coordinate<T>], origin_top_left: bool) {
-    let min_row = matrix.iter().map(|c| c.row).min().unwrap();
-    let max_row = matrix.iter().map(|c| c.row).max().unwrap();
-    let min_col = matrix.iter().map(|c| c.col).min().unwrap();
-    let max_col = matrix.iter().map(|c| c.col).max().unwrap();
-    let row_iter = if origin_top_left {
-        range_inclusive(min_row, max_row).collect_vec()
-    } else {
-        range_inclusive(min_row, max_row)
-            .collect_vec()
-            .into_iter()
-            .rev()
-            .collect_vec()
-    };
-    for row in row_iter {
-        for col in range_inclusive(min_col, max_col) {
-            let c = Coordinate { row, col };
-            if matrix.contains(&c) {
-                print!("#");
-            } else {
-                print!(".");
-            }
+impl<T: PrimInt> AddAssign for Coordinate<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: PrimInt> SubAssign for Coordinate<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+// Scale, e.g. a direction by a number of steps
+impl<T: PrimInt> Mul<T> for Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Coordinate {
+            row: self.row * rhs,
+            col: self.col * rhs,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Neg for Coordinate<T> {
+    type Output = Coordinate<T>;
+
+    fn neg(self) -> Self::Output {
+        Coordinate {
+            row: -self.row,
+            col: -self.col,
+        }
+    }
+}
+
+/// An inclusive rectangle of coordinates, from `min` in the top left to `max` in the bottom right
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct BoundingBox<T: PrimInt> {
+    pub min: Coordinate<T>,
+    pub max: Coordinate<T>,
+}
+
+impl<T: PrimInt> BoundingBox<T> {
+    pub fn new(min: Coordinate<T>, max: Coordinate<T>) -> BoundingBox<T> {
+        BoundingBox { min, max }
+    }
+
+    /// The cells of a `rows` x `cols` grid starting at 0, e.g. a `Vec<Vec<_>>`.
+    /// The grid must not be empty
+    pub fn from_size(rows: T, cols: T) -> BoundingBox<T> {
+        BoundingBox {
+            min: Coordinate::new(T::zero(), T::zero()),
+            max: Coordinate::new(rows - T::one(), cols - T::one()),
+        }
+    }
+
+    /// The smallest box containing every coordinate, or None if there are none
+    pub fn from_coordinates<'a, I: IntoIterator<Item = &'a Coordinate<T>>>(
+        coordinates: I,
+    ) -> Option<BoundingBox<T>>
+    where
+        T: 'a,
+    {
+        let mut coordinates = coordinates.into_iter();
+        let first = coordinates.next()?;
+        let mut bounds = BoundingBox::new(*first, *first);
+        for c in coordinates {
+            bounds.expand(c);
         }
-        println!();
+        Some(bounds)
+    }
+
+    pub fn contains(&self, c: &Coordinate<T>) -> bool {
+        (self.min.row..=self.max.row).contains(&c.row)
+            && (self.min.col..=self.max.col).contains(&c.col)
+    }
+
+    /// Grow the box just enough to contain `c`
+    pub fn expand(&mut self, c: &Coordinate<T>) {
+        self.min.row = min(self.min.row, c.row);
+        self.min.col = min(self.min.col, c.col);
+        self.max.row = max(self.max.row, c.row);
+        self.max.col = max(self.max.col, c.col);
+    }
+
+    /// The overlap of the two boxes, or None if they don't overlap
+    pub fn intersect(&self, other: &BoundingBox<T>) -> Option<BoundingBox<T>> {
+        let bounds = BoundingBox {
+            min: Coordinate::new(
+                max(self.min.row, other.min.row),
+                max(self.min.col, other.min.col),
+            ),
+            max: Coordinate::new(
+                min(self.max.row, other.max.row),
+                min(self.max.col, other.max.col),
+            ),
+        };
+        (bounds.min.row <= bounds.max.row && bounds.min.col <= bounds.max.col).then_some(bounds)
     }
-    println!();
+
+    pub fn height(&self) -> T {
+        self.max.row - self.min.row + T::one()
+    }
+
+    pub fn width(&self) -> T {
+        self.max.col - self.min.col + T::one()
+    }
+
+    pub fn area(&self) -> T {
+        self.height() * self.width()
+    }
+
+    /// Every cell in the box, in row-major order
+    pub fn iter(&self) -> impl Iterator<Item = Coordinate<T>> {
+        let cols = (self.min.col, self.max.col);
+        range_inclusive(self.min.row, self.max.row).flat_map(move |row| {
+            range_inclusive(cols.0, cols.1).map(move |col| Coordinate::new(row, col))
+        })
+    }
+}
+
+/// Positions with a fixed set of neighbours, so searches and flood fills can be written once
+/// for both square and hex grids
+pub trait Neighbours: Sized {
+    /// The region neighbours can be restricted to
+    type Bounds;
+
+    /// Every neighbour, only skipping any which would overflow
+    fn neighbours(&self) -> Vec<Self>;
+
+    /// The neighbours inside `bounds`
+    fn neighbours_within(&self, bounds: &Self::Bounds) -> Vec<Self>;
+}
+
+impl<T: PrimInt> Neighbours for Coordinate<T> {
+    type Bounds = BoundingBox<T>;
+
+    fn neighbours(&self) -> Vec<Self> {
+        let everywhere = BoundingBox::new(
+            Coordinate::new(T::min_value(), T::min_value()),
+            Coordinate::new(T::max_value(), T::max_value()),
+        );
+        self.get_adjacent_points(&everywhere)
+    }
+
+    fn neighbours_within(&self, bounds: &BoundingBox<T>) -> Vec<Self> {
+        self.get_adjacent_points(bounds)
+    }
+}
+
+// Print the bounding box of the coordinates, with # if the coordinate is present and . if its absent
+pub fn print_coordinates<T: PrimInt + Display + Hash>(
+    matrix: &[Coordinate<T>],
+    origin_top_left: bool,
+) {
+    println!(
+        "{}",
+        render_coordinates(matrix, None, origin_top_left, Rulers::default())
+    );
 }
 
 #[cfg(test)]
@@ -199,11 +555,122 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn signed_distances() -> color_eyre::Result<()> {
+        let a = Coordinate::new(-2i64, 3);
+        let b = Coordinate::new(1, -1);
+        assert_eq!(a.manhattan_distance(&b), 7);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+        assert_eq!(a.squared_euclidean_distance(&b), 25);
+        Ok(())
+    }
+
+    #[test]
+    fn unsigned_distances() -> color_eyre::Result<()> {
+        let a = IndexingCoordinate::new(5, 1);
+        let b = IndexingCoordinate::new(2, 7);
+        assert_eq!(a.abs_diff(&b), Coordinate::new(3, 6));
+        assert_eq!(a.manhattan_distance_as::<usize>(&b), 9);
+        assert_eq!(b.chebyshev_distance_as::<usize>(&a), 6);
+        let (c, d) = (Coordinate::new(u32::MAX, 0), Coordinate::new(0, u32::MAX));
+        assert_eq!(c.manhattan_distance_as::<u128>(&d), 2 * u32::MAX as u128);
+        assert_eq!(a.squared_euclidean_distance_as::<usize>(&b), 45);
+        Ok(())
+    }
+
+    #[test]
+    fn widened_distances_do_not_overflow() -> color_eyre::Result<()> {
+        let a = Coordinate::new(i32::MIN, i32::MIN);
+        let b = Coordinate::new(i32::MAX, i32::MAX);
+        assert_eq!(a.manhattan_distance_as::<i64>(&b), 2 * (u32::MAX as i64));
+        assert_eq!(
+            a.squared_euclidean_distance_as::<i128>(&b),
+            2 * (u32::MAX as i128).pow(2)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn arithmetic_operators() -> color_eyre::Result<()> {
+        let direction = Coordinate::new(-1, 2);
+        let mut position = Coordinate::new(5, 5);
+        assert_eq!(direction * 3, Coordinate::new(-3, 6));
+        assert_eq!(-direction, Coordinate::new(1, -2));
+        position += direction * 2;
+        assert_eq!(position, Coordinate::new(3, 9));
+        position -= direction;
+        assert_eq!(position, Coordinate::new(4, 7));
+        Ok(())
+    }
+
+    #[test]
+    fn reading_order() -> color_eyre::Result<()> {
+        let sorted = vec![(1, 0), (0, 2), (0, 1), (1, -1)]
+            .into_iter()
+            .map(Coordinate::from)
+            .sorted()
+            .collect_vec();
+        assert_eq!(
+            sorted,
+            vec![(0, 1), (0, 2), (1, -1), (1, 0)]
+                .into_iter()
+                .map(Coordinate::from)
+                .collect_vec()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn checked_arithmetic() -> color_eyre::Result<()> {
+        let origin = IndexingCoordinate::new(0, 3);
+        assert_eq!(origin.checked_sub(&Coordinate::new(1, 0)), None);
+        assert_eq!(
+            origin.checked_sub(&Coordinate::new(0, 3)),
+            Some(Coordinate::new(0, 0))
+        );
+        assert_eq!(
+            Coordinate::new(usize::MAX, 0).checked_add(&Coordinate::new(1, 0)),
+            None
+        );
+        assert_eq!(origin.checked_add_signed(Coordinate::new(-1, 0)), None);
+        assert_eq!(
+            origin.checked_add_signed(Coordinate::new(1, -3)),
+            Some(Coordinate::new(1, 0))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn integer_conversions() -> color_eyre::Result<()> {
+        let c = IndexingCoordinate::new(3, 4);
+        assert_eq!(Coordinate::<i64>::try_from(c)?, Coordinate::new(3i64, 4));
+        assert_eq!(PosCoordinate::try_from(c)?, Coordinate::new(3u64, 4));
+        assert_eq!(
+            IndexingCoordinate::try_from(Coordinate::new(2i32, 7))?,
+            Coordinate::new(2, 7)
+        );
+        assert!(IndexingCoordinate::try_from(Coordinate::new(-1i64, 0)).is_err());
+        assert!(Coordinate::<i32>::try_from(Coordinate::new(0u64, u64::MAX)).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn to_indexing_checks_bounds() -> color_eyre::Result<()> {
+        assert_eq!(
+            Coordinate::new(1isize, 2).to_indexing(2, 3),
+            Some(Coordinate::new(1, 2))
+        );
+        assert_eq!(Coordinate::new(2isize, 0).to_indexing(2, 3), None);
+        assert_eq!(Coordinate::new(0isize, 3).to_indexing(2, 3), None);
+        assert_eq!(Coordinate::new(-1isize, 0).to_indexing(2, 3), None);
+        Ok(())
+    }
+
     #[test]
     fn get_between_col_forward() -> color_eyre::Result<()> {
         let start_coord = Coordinate { row: 0, col: 0 };
         let end_coord = Coordinate { row: 5, col: 0 };
-        let between = start_coord.get_between(&end_coord);
+        let between = start_coord.get_between(&end_coord)?.collect_vec();
         assert_eq!(
             between,
             vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]
@@ -218,7 +685,7 @@ mod tests {
     fn get_between_col_reverse() -> color_eyre::Result<()> {
         let start_coord = Coordinate { row: 5, col: 0 };
         let end_coord = Coordinate { row: 0, col: 0 };
-        let between = start_coord.get_between(&end_coord);
+        let between = start_coord.get_between(&end_coord)?.collect_vec();
         assert_eq!(
             between,
             vec![(0, 0), (1, 0), (2, 0), (3, 0), (4, 0), (5, 0)]
@@ -233,7 +700,7 @@ mod tests {
     fn get_between_row_forward() -> color_eyre::Result<()> {
         let start_coord = Coordinate { row: 0, col: 0 };
         let end_coord = Coordinate { row: 0, col: 5 };
-        let between = start_coord.get_between(&end_coord);
+        let between = start_coord.get_between(&end_coord)?.collect_vec();
         assert_eq!(
             between,
             vec![(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)]
@@ -248,7 +715,7 @@ mod tests {
     fn get_between_row_reverse() -> color_eyre::Result<()> {
         let start_coord = Coordinate { row: 0, col: 5 };
         let end_coord = Coordinate { row: 0, col: 0 };
-        let between = start_coord.get_between(&end_coord);
+        let between = start_coord.get_between(&end_coord)?.collect_vec();
         assert_eq!(
             between,
             vec![(0, 0), (0, 1), (0, 2), (0, 3), (0, 4), (0, 5)]
@@ -258,4 +725,167 @@ mod tests {
         );
         Ok(())
     }
+
+    #[test]
+    fn bounding_box_from_coordinates() {
+        let coordinates = [(2, -1), (0, 3), (1, 1)].map(Coordinate::from);
+        let bounds = BoundingBox::from_coordinates(&coordinates).unwrap();
+        assert_eq!(
+            bounds,
+            BoundingBox::new(Coordinate::new(0, -1), Coordinate::new(2, 3))
+        );
+        assert_eq!((bounds.height(), bounds.width(), bounds.area()), (3, 5, 15));
+        assert!(bounds.contains(&Coordinate::new(2, 3)));
+        assert!(!bounds.contains(&Coordinate::new(3, 0)));
+        assert_eq!(BoundingBox::<i32>::from_coordinates(&[]), None);
+    }
+
+    #[test]
+    fn bounding_box_expand_and_intersect() {
+        let mut a = BoundingBox::from_size(2, 2);
+        a.expand(&Coordinate::new(3, 1));
+        assert_eq!(
+            a,
+            BoundingBox::new(Coordinate::new(0, 0), Coordinate::new(3, 1))
+        );
+        let b = BoundingBox::new(Coordinate::new(1, 1), Coordinate::new(5, 5));
+        assert_eq!(
+            a.intersect(&b),
+            Some(BoundingBox::new(
+                Coordinate::new(1, 1),
+                Coordinate::new(3, 1)
+            ))
+        );
+        let c = BoundingBox::new(Coordinate::new(0, 2), Coordinate::new(0, 2));
+        assert_eq!(a.intersect(&c), None);
+    }
+
+    #[test]
+    fn bounding_box_iter() {
+        let bounds = BoundingBox::new(Coordinate::new(-1, 0), Coordinate::new(0, 1));
+        assert_eq!(
+            bounds.iter().collect_vec(),
+            [(-1, 0), (-1, 1), (0, 0), (0, 1)].map(Coordinate::from)
+        );
+    }
+
+    #[test]
+    fn adjacent_points_in_bounds() {
+        let bounds = BoundingBox::from_size(3usize, 2);
+        assert_eq!(
+            Coordinate::new(0usize, 0).get_adjacent_points(&bounds),
+            vec![Coordinate::new(1, 0), Coordinate::new(0, 1)]
+        );
+        assert_eq!(
+            Coordinate::new(1usize, 1)
+                .get_adjacent_points_diagonal(&bounds)
+                .len(),
+            5
+        );
+        let offset = BoundingBox::new(Coordinate::new(-5, -5), Coordinate::new(-4, -4));
+        assert_eq!(
+            Coordinate::new(-5, -5).get_adjacent_points_diagonal(&offset),
+            vec![
+                Coordinate::new(-4, -5),
+                Coordinate::new(-5, -4),
+                Coordinate::new(-4, -4)
+            ]
+        );
+    }
+
+    #[test]
+    fn wrap_into_tile() {
+        let bounds = BoundingBox::from_size(3, 4);
+        assert_eq!(
+            Coordinate::new(1, 2).wrap(&bounds),
+            (Coordinate::new(1, 2), Coordinate::new(0, 0))
+        );
+        assert_eq!(
+            Coordinate::new(-1, 9).wrap(&bounds),
+            (Coordinate::new(2, 1), Coordinate::new(-1, 2))
+        );
+        assert_eq!(
+            Coordinate::new(-3, -4).wrap(&bounds),
+            (Coordinate::new(0, 0), Coordinate::new(-1, -1))
+        );
+        let offset = BoundingBox::new(Coordinate::new(10, -2), Coordinate::new(11, -1));
+        assert_eq!(
+            Coordinate::new(9, 0).wrap(&offset),
+            (Coordinate::new(11, -2), Coordinate::new(-1, 1))
+        );
+    }
+
+    #[test]
+    fn adjacent_points_wrapping() {
+        let bounds = BoundingBox::from_size(2i64, 3);
+        assert_eq!(
+            Coordinate::new(0, 2).get_adjacent_points_wrapping(&bounds),
+            vec![
+                (Coordinate::new(1, 2), Coordinate::new(-1, 0)),
+                (Coordinate::new(1, 2), Coordinate::new(0, 0)),
+                (Coordinate::new(0, 1), Coordinate::new(0, 0)),
+                (Coordinate::new(0, 0), Coordinate::new(0, 1)),
+            ]
+        );
+        let diagonal = Coordinate::new(0, 0).get_adjacent_points_diagonal_wrapping(&bounds);
+        assert_eq!(diagonal.len(), 8);
+        assert!(diagonal.contains(&(Coordinate::new(1, 2), Coordinate::new(-1, -1))));
+    }
+
+    #[test]
+    fn get_between_diagonal() -> color_eyre::Result<()> {
+        let start = Coordinate::new(3u32, 0);
+        let end = Coordinate::new(0, 3);
+        assert_eq!(
+            start.get_between(&end)?.collect_vec(),
+            [(0, 3), (1, 2), (2, 1), (3, 0)].map(Coordinate::from)
+        );
+        let start = Coordinate::new(-1, -1);
+        assert_eq!(
+            start.get_between(&Coordinate::new(1, 1))?.collect_vec(),
+            [(-1, -1), (0, 0), (1, 1)].map(Coordinate::from)
+        );
+        assert_eq!(start.get_between(&start)?.collect_vec(), vec![start]);
+        Ok(())
+    }
+
+    #[test]
+    fn get_between_not_a_line() {
+        let start = Coordinate::new(0, 0);
+        assert!(start.get_between(&Coordinate::new(1, 2)).is_err());
+    }
+
+    #[test]
+    fn bresenham_lines() {
+        let start = Coordinate::new(0, 0);
+        assert_eq!(
+            start.bresenham(&Coordinate::new(1, 4)).collect_vec(),
+            [(0, 0), (0, 1), (1, 2), (1, 3), (1, 4)].map(Coordinate::from)
+        );
+        assert_eq!(
+            Coordinate::new(2, 0).bresenham(&start).collect_vec(),
+            [(2, 0), (1, 0), (0, 0)].map(Coordinate::from)
+        );
+        let end = Coordinate::new(-7i64, 3);
+        let line = start.bresenham(&end).collect_vec();
+        assert_eq!((line[0], line[line.len() - 1]), (start, end));
+        assert!(line
+            .iter()
+            .tuple_windows()
+            .all(|(a, b)| a.chebyshev_distance(b) == 1));
+        assert_eq!(start.bresenham(&start).collect_vec(), vec![start]);
+    }
+
+    #[test]
+    fn neighbours_trait() {
+        let corner = Coordinate::new(0u8, 255);
+        assert_eq!(
+            corner.neighbours(),
+            vec![Coordinate::new(1, 255), Coordinate::new(0, 254)]
+        );
+        assert_eq!(
+            corner.neighbours_within(&BoundingBox::new(Coordinate::new(0, 254), corner)),
+            vec![Coordinate::new(0, 254)]
+        );
+    }
 }
@@ -0,0 +1,184 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states starts repeating.
+/// State `start + length` is the same as state `start`, and there's no earlier repeat
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The earliest step with the same state as step `n`
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+// Floyd and Brent only store a couple of states, but compare many more of them than
+// find_repeat, so prefer them when states are large and cheap to compare.
+// All of them loop forever if `step` never repeats a state
+
+/// Floyd's tortoise and hare
+pub fn floyd<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        let halfway = step(&hare);
+        hare = step(&halfway);
+    }
+    // The hare is now a multiple of the cycle length ahead, so they meet again at the start
+    let mut start = 0;
+    tortoise = initial;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm, which finds the length first using fewer steps than Floyd
+pub fn brent<S: Clone + PartialEq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+    // Start the hare one cycle ahead, then they meet at the start of the cycle
+    tortoise = initial.clone();
+    hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+// Step until either reaching step `limit` or seeing a state for the second time.
+// Returns every state seen, indexed by step
+fn history<S: Clone + Hash + Eq>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    limit: Option<usize>,
+) -> (Vec<S>, Option<Cycle>) {
+    let mut seen = HashMap::new();
+    let mut states = vec![];
+    let mut state = initial;
+    loop {
+        if limit == Some(states.len()) {
+            return (states, None);
+        }
+        if let Some(&start) = seen.get(&state) {
+            let length = states.len() - start;
+            return (states, Some(Cycle { start, length }));
+        }
+        seen.insert(state.clone(), states.len());
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Remember every state until one repeats. Each state is only computed once
+pub fn find_repeat<S: Clone + Hash + Eq>(initial: S, step: impl FnMut(&S) -> S) -> Cycle {
+    history(initial, step, None)
+        .1
+        .expect("History only stops early with a limit")
+}
+
+/// The state after `n` steps, skipping ahead once the states start repeating
+pub fn nth_state<S: Clone + Hash + Eq>(initial: S, step: impl FnMut(&S) -> S, n: usize) -> S {
+    // Step usize::MAX can't be reached without repeating, so it doesn't need a limit
+    let (states, cycle) = history(initial, step, n.checked_add(1));
+    match cycle {
+        Some(cycle) => states[cycle.equivalent_step(n)].clone(),
+        None => states[n].clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 0 -> 1 -> 2 -> 3 -> 4 -> 5 -> 2
+    fn step(x: &u32) -> u32 {
+        if *x == 5 {
+            2
+        } else {
+            x + 1
+        }
+    }
+
+    #[test]
+    fn algorithms_agree() {
+        let expected = Cycle {
+            start: 2,
+            length: 4,
+        };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        assert_eq!(find_repeat(0, step), expected);
+        // Already in the cycle
+        let in_cycle = Cycle {
+            start: 0,
+            length: 4,
+        };
+        assert_eq!(floyd(3, step), in_cycle);
+        assert_eq!(brent(3, step), in_cycle);
+        assert_eq!(find_repeat(3, step), in_cycle);
+    }
+
+    #[test]
+    fn longer_sequence() {
+        let step = |x: &u64| (x * x + 1) % 1009;
+        let cycle = find_repeat(7, step);
+        assert_eq!(floyd(7, step), cycle);
+        assert_eq!(brent(7, step), cycle);
+    }
+
+    #[test]
+    fn skip_ahead() {
+        assert_eq!(nth_state(0, step, 0), 0);
+        assert_eq!(nth_state(0, step, 5), 5);
+        assert_eq!(nth_state(0, step, 6), 2);
+        assert_eq!(nth_state(0, step, 1_000_000_000), 4);
+        let cycle = Cycle {
+            start: 2,
+            length: 4,
+        };
+        assert_eq!(cycle.equivalent_step(1), 1);
+        assert_eq!(cycle.equivalent_step(11), 3);
+    }
+
+    #[test]
+    fn skip_ahead_to_max() {
+        assert_eq!(nth_state(0, step, usize::MAX), 3);
+        assert_eq!(nth_state(3, step, usize::MAX), 2);
+    }
+}
@@ -0,0 +1,315 @@
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::mem;
+
+use color_eyre::eyre::{eyre, Result};
+use itertools::Itertools;
+use petgraph::algo::{tarjan_scc, toposort};
+use petgraph::dot::{Config, Dot};
+use petgraph::graph::NodeIndex;
+use petgraph::visit::EdgeRef;
+use petgraph::{Directed, EdgeType, Graph};
+
+use crate::coordinate::IndexingCoordinate;
+use crate::matrix::Matrix;
+
+/// A petgraph graph where every node has a unique label, e.g. a name or a coordinate,
+/// so nodes can be looked up by label instead of by index.
+/// `graph` is public so any petgraph algorithm can be run on it directly
+#[derive(Clone, Debug)]
+pub struct LabelledGraph<L, E, Ty: EdgeType = Directed> {
+    pub graph: Graph<L, E, Ty>,
+    indices: HashMap<L, NodeIndex>,
+}
+
+/// The smallest total edge weight which splits a graph in two, and the labels on one side
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MinCut<L> {
+    pub weight: u64,
+    pub partition: Vec<L>,
+}
+
+impl<L: Clone + Eq + Hash, E, Ty: EdgeType> Default for LabelledGraph<L, E, Ty> {
+    fn default() -> Self {
+        LabelledGraph {
+            graph: Graph::default(),
+            indices: HashMap::new(),
+        }
+    }
+}
+
+impl<L: Clone + Eq + Hash, E, Ty: EdgeType> LabelledGraph<L, E, Ty> {
+    pub fn new() -> Self {
+        LabelledGraph::default()
+    }
+
+    /// Build from (from, to, weight) edges, adding nodes the first time their label is seen
+    pub fn from_edges<I: IntoIterator<Item = (L, L, E)>>(edges: I) -> Self {
+        let mut graph = LabelledGraph::new();
+        for (from, to, weight) in edges {
+            graph.add_edge(from, to, weight);
+        }
+        graph
+    }
+
+    /// The node with this label, adding it if there isn't one yet
+    pub fn add_node(&mut self, label: L) -> NodeIndex {
+        if let Some(&index) = self.indices.get(&label) {
+            return index;
+        }
+        let index = self.graph.add_node(label.clone());
+        self.indices.insert(label, index);
+        index
+    }
+
+    pub fn add_edge(&mut self, from: L, to: L, weight: E) {
+        let (from, to) = (self.add_node(from), self.add_node(to));
+        self.graph.add_edge(from, to, weight);
+    }
+
+    pub fn index(&self, label: &L) -> Option<NodeIndex> {
+        self.indices.get(label).copied()
+    }
+
+    pub fn label(&self, index: NodeIndex) -> &L {
+        &self.graph[index]
+    }
+
+    /// Groups of nodes which can all reach each other, in reverse topological order
+    pub fn strongly_connected_components(&self) -> Vec<Vec<L>> {
+        tarjan_scc(&self.graph)
+            .into_iter()
+            .map(|component| self.labels(&component))
+            .collect_vec()
+    }
+
+    /// Every node, ordered so edges only go forwards. Fails if there's a cycle
+    pub fn topological_sort(&self) -> Result<Vec<L>>
+    where
+        L: Debug,
+    {
+        toposort(&self.graph, None)
+            .map(|order| self.labels(&order))
+            .map_err(|cycle| {
+                eyre!(
+                    "Graph has a cycle through {:?}",
+                    self.label(cycle.node_id())
+                )
+            })
+    }
+
+    /// The global minimum cut, treating every edge as undirected, using Stoer-Wagner.
+    /// None if there are fewer than 2 nodes
+    pub fn min_cut(&self, weight: impl Fn(&E) -> u64) -> Option<MinCut<L>> {
+        let (weight, partition) = stoer_wagner(&self.graph, weight)?;
+        Some(MinCut {
+            weight,
+            partition: self.labels(&partition),
+        })
+    }
+
+    /// Graphviz DOT, using Debug for the labels. Edge labels are usually noise on puzzle
+    /// sized graphs, so they're only included if asked for
+    pub fn to_dot(&self, edge_labels: bool) -> String
+    where
+        L: Debug,
+        E: Debug,
+    {
+        let config: &[Config] = if edge_labels {
+            &[]
+        } else {
+            &[Config::EdgeNoLabel]
+        };
+        format!("{:?}", Dot::with_config(&self.graph, config))
+    }
+
+    fn labels(&self, indices: &[NodeIndex]) -> Vec<L> {
+        indices
+            .iter()
+            .map(|&index| self.label(index).clone())
+            .collect_vec()
+    }
+}
+
+impl LabelledGraph<IndexingCoordinate, ()> {
+    /// A node for each cell where `passable` holds, with an edge to each orthogonal neighbour
+    /// that's also passable, if `can_move` allows moving from the cell to the neighbour.
+    /// Use `|_, _| true` for a plain maze, or check e.g. slopes for one way moves
+    pub fn from_grid<T>(
+        matrix: &Matrix<T>,
+        passable: impl Fn(&T) -> bool,
+        can_move: impl Fn((IndexingCoordinate, &T), (IndexingCoordinate, &T)) -> bool,
+    ) -> Self {
+        let mut graph = LabelledGraph::new();
+        let Some(bounds) = matrix.bounds() else {
+            return graph;
+        };
+        for cell in bounds.iter() {
+            let value = &matrix[cell];
+            if !passable(value) {
+                continue;
+            }
+            graph.add_node(cell);
+            for (neighbour, other) in matrix.neighbours(cell) {
+                if passable(other) && can_move((cell, value), (neighbour, other)) {
+                    graph.add_edge(cell, neighbour, ());
+                }
+            }
+        }
+        graph
+    }
+}
+
+// Returns the cut weight, and the nodes on one side of it
+fn stoer_wagner<N, E, Ty: EdgeType>(
+    graph: &Graph<N, E, Ty>,
+    weight: impl Fn(&E) -> u64,
+) -> Option<(u64, Vec<NodeIndex>)> {
+    let n = graph.node_count();
+    if n < 2 {
+        return None;
+    }
+    let mut adjacency: Vec<HashMap<usize, u64>> = vec![HashMap::new(); n];
+    for edge in graph.edge_references() {
+        let (a, b) = (edge.source().index(), edge.target().index());
+        if a != b {
+            *adjacency[a].entry(b).or_default() += weight(edge.weight());
+            *adjacency[b].entry(a).or_default() += weight(edge.weight());
+        }
+    }
+    // The original nodes which have been merged into each node
+    let mut members = (0..n).map(|i| vec![i]).collect_vec();
+    let mut active = (0..n).collect_vec();
+    let mut best: Option<(u64, Vec<usize>)> = None;
+    while active.len() > 1 {
+        // Add the most tightly connected node each time. The last two added are s and t,
+        // and the weight connecting t is the smallest cut between them
+        let mut added = vec![false; n];
+        let mut connection: HashMap<usize, u64> = HashMap::new();
+        let mut queue = BinaryHeap::from([(0, active[0])]);
+        let mut order = vec![];
+        let mut cut = 0;
+        while let Some((w, v)) = queue.pop() {
+            if added[v] || w != connection.get(&v).copied().unwrap_or(0) {
+                continue;
+            }
+            added[v] = true;
+            order.push(v);
+            cut = w;
+            for (&u, &uw) in &adjacency[v] {
+                if !added[u] {
+                    let total = connection.entry(u).or_default();
+                    *total += uw;
+                    queue.push((*total, u));
+                }
+            }
+        }
+        if order.len() < active.len() {
+            // Disconnected, so there's a free cut around everything reached
+            let partition = order.iter().flat_map(|&v| members[v].clone()).collect_vec();
+            best = Some((0, partition));
+            break;
+        }
+        let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
+        if best.as_ref().is_none_or(|(weight, _)| cut < *weight) {
+            best = Some((cut, members[t].clone()));
+        }
+        // Merge t into s
+        let merged = mem::take(&mut members[t]);
+        members[s].extend(merged);
+        for (u, w) in mem::take(&mut adjacency[t]) {
+            adjacency[u].remove(&t);
+            if u != s {
+                *adjacency[s].entry(u).or_default() += w;
+                *adjacency[u].entry(s).or_default() += w;
+            }
+        }
+        active.retain(|&v| v != t);
+    }
+    best.map(|(weight, partition)| {
+        (
+            weight,
+            partition.into_iter().map(NodeIndex::new).collect_vec(),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use petgraph::Undirected;
+
+    use super::*;
+
+    fn parse_edges(input: &str) -> Vec<(&str, &str, ())> {
+        input
+            .lines()
+            .flat_map(|line| {
+                let (from, to) = line.split_once(": ").unwrap();
+                to.split(' ').map(move |to| (from, to, ()))
+            })
+            .collect_vec()
+    }
+
+    #[test]
+    fn components_and_order() -> color_eyre::Result<()> {
+        let graph: LabelledGraph<_, _> =
+            LabelledGraph::from_edges(parse_edges("a: b c\nb: d\nc: d\nd: e\ne: d"));
+        let components = graph.strongly_connected_components();
+        assert_eq!(components.len(), 4);
+        assert!(components
+            .iter()
+            .any(|c| c.iter().sorted().collect_vec() == [&"d", &"e"]));
+        assert!(graph.topological_sort().is_err());
+
+        let acyclic: LabelledGraph<_, _> =
+            LabelledGraph::from_edges(parse_edges("a: b c\nb: d\nc: d"));
+        let order = acyclic.topological_sort()?;
+        assert_eq!((order[0], order[3]), ("a", "d"));
+        Ok(())
+    }
+
+    #[test]
+    fn min_cut_example() {
+        // 2023 day 25 example, where cutting 3 wires splits it into groups of 9 and 6
+        let input = "jqt: rhn xhk nvd\nrsh: frs pzl lsr\nxhk: hfx\ncmg: qnr nvd lhk bvb\n\
+            rhn: xhk bvb hfx\nbvb: xhk hfx\npzl: lsr hfx nvd\nqnr: nvd\nntq: jqt hfx bvb xhk\n\
+            nvd: lhk\nlsr: lhk\nrzs: qnr cmg lsr rsh\nfrs: qnr lhk lsr";
+        let graph: LabelledGraph<_, _, Undirected> = LabelledGraph::from_edges(parse_edges(input));
+        let cut = graph.min_cut(|_| 1).unwrap();
+        assert_eq!(cut.weight, 3);
+        assert!([6, 9].contains(&cut.partition.len()));
+
+        let disconnected: LabelledGraph<_, _> =
+            LabelledGraph::from_edges(vec![("a", "b", 5), ("c", "d", 5)]);
+        assert_eq!(disconnected.min_cut(|&w| w).unwrap().weight, 0);
+        assert_eq!(LabelledGraph::<&str, u64>::new().min_cut(|&w| w), None);
+    }
+
+    #[test]
+    fn grid_with_slopes() -> color_eyre::Result<()> {
+        let matrix = Matrix::from_rows(vec![
+            "..#".chars().collect_vec(),
+            ".>.".chars().collect_vec(),
+        ])?;
+        // Slopes can only be left going right
+        let graph = LabelledGraph::from_grid(
+            &matrix,
+            |&c| c != '#',
+            |(from, &c), (to, _)| c != '>' || to.col > from.col,
+        );
+        assert_eq!(graph.graph.node_count(), 5);
+        let slope = graph.index(&IndexingCoordinate::new(1, 1)).unwrap();
+        let exits = graph
+            .graph
+            .neighbors(slope)
+            .map(|index| *graph.label(index))
+            .collect_vec();
+        assert_eq!(exits, vec![IndexingCoordinate::new(1, 2)]);
+        assert_eq!(graph.graph.edge_count(), 8);
+        let dot = graph.to_dot(false);
+        assert!(dot.contains("Coordinate { row: 1, col: 1 }"));
+        assert!(!dot.contains("label = \"()\""));
+        Ok(())
+    }
+}
@@ -0,0 +1,369 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use color_eyre::eyre::{eyre, Report};
+use itertools::Itertools;
+use nom::branch::alt;
+use nom::bytes::complete::tag;
+use nom::character::complete::char;
+use nom::combinator::value;
+use nom::multi::separated_list1;
+use nom::IResult;
+use num::{abs, PrimInt, Signed};
+
+use crate::coordinate::Neighbours;
+
+/// A cell of a hex grid in axial coordinates, with flat topped hexes so N and S are neighbours.
+/// The third cube coordinate is `s()`, and q + r + s is always 0
+#[derive(Clone, Copy, Debug, Default, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct HexCoordinate<T: PrimInt + Signed> {
+    pub q: T,
+    pub r: T,
+}
+
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HexDirection {
+    N,
+    NE,
+    SE,
+    S,
+    SW,
+    NW,
+}
+
+impl HexDirection {
+    /// Clockwise, starting from north
+    pub const ALL: [HexDirection; 6] = [
+        HexDirection::N,
+        HexDirection::NE,
+        HexDirection::SE,
+        HexDirection::S,
+        HexDirection::SW,
+        HexDirection::NW,
+    ];
+
+    pub fn offset<T: PrimInt + Signed>(&self) -> HexCoordinate<T> {
+        let (q, r) = match self {
+            HexDirection::N => (0, -1),
+            HexDirection::NE => (1, -1),
+            HexDirection::SE => (1, 0),
+            HexDirection::S => (0, 1),
+            HexDirection::SW => (-1, 1),
+            HexDirection::NW => (-1, 0),
+        };
+        HexCoordinate::new(
+            T::from(q).expect("Signed types fit an i8"),
+            T::from(r).expect("Signed types fit an i8"),
+        )
+    }
+
+    pub fn opposite(&self) -> HexDirection {
+        self.rotate(3)
+    }
+
+    /// Turn clockwise by `steps` sixths of a turn
+    pub fn rotate(&self, steps: usize) -> HexDirection {
+        let index = HexDirection::ALL
+            .iter()
+            .position(|d| d == self)
+            .expect("All directions are listed");
+        HexDirection::ALL[(index + steps) % 6]
+    }
+}
+
+impl FromStr for HexDirection {
+    type Err = Report;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_direction(s) {
+            Ok(("", direction)) => Ok(direction),
+            _ => Err(eyre!("Invalid hex direction {}", s)),
+        }
+    }
+}
+
+// The two letter directions must be tried first, so "ne" isn't read as "n"
+pub fn parse_direction(input: &str) -> IResult<&str, HexDirection> {
+    alt((
+        value(HexDirection::NE, tag("ne")),
+        value(HexDirection::NW, tag("nw")),
+        value(HexDirection::SE, tag("se")),
+        value(HexDirection::SW, tag("sw")),
+        value(HexDirection::N, tag("n")),
+        value(HexDirection::S, tag("s")),
+    ))(input)
+}
+
+/// Comma separated directions, e.g. `ne,ne,s`
+pub fn parse_directions(input: &str) -> IResult<&str, Vec<HexDirection>> {
+    separated_list1(char(','), parse_direction)(input)
+}
+
+impl<T: PrimInt + Signed> HexCoordinate<T> {
+    pub fn new(q: T, r: T) -> HexCoordinate<T> {
+        HexCoordinate { q, r }
+    }
+
+    pub fn from_cube(q: T, r: T, s: T) -> HexCoordinate<T> {
+        debug_assert!(q + r + s == T::zero(), "Cube coordinates must sum to 0");
+        HexCoordinate { q, r }
+    }
+
+    pub fn s(&self) -> T {
+        -self.q - self.r
+    }
+
+    pub fn neighbour(&self, direction: HexDirection) -> HexCoordinate<T> {
+        *self + direction.offset()
+    }
+
+    /// The 6 surrounding hexes, clockwise from north. Hex grids are unbounded, so there are always 6
+    pub fn get_adjacent_points(&self) -> Vec<HexCoordinate<T>> {
+        HexDirection::ALL
+            .iter()
+            .map(|&direction| self.neighbour(direction))
+            .collect_vec()
+    }
+
+    /// Follow each direction in turn, e.g. from `parse_directions`
+    pub fn walk<'a, I: IntoIterator<Item = &'a HexDirection>>(&self, directions: I) -> Self {
+        directions
+            .into_iter()
+            .fold(*self, |position, &direction| position.neighbour(direction))
+    }
+
+    /// The fewest steps between the two hexes
+    pub fn distance(&self, other: &HexCoordinate<T>) -> T {
+        let diff = *other - *self;
+        (abs(diff.q) + abs(diff.r) + abs(diff.s())) / (T::one() + T::one())
+    }
+
+    /// Every hex exactly `radius` steps away, clockwise from the one due north
+    pub fn ring(&self, radius: T) -> Vec<HexCoordinate<T>> {
+        if radius.is_zero() {
+            return vec![*self];
+        }
+        let mut position = *self + HexDirection::N.offset() * radius;
+        let mut ring = vec![];
+        for direction in HexDirection::ALL {
+            // From the north corner, the first side runs south east
+            let side = direction.rotate(2);
+            for _ in num::range(T::zero(), radius) {
+                ring.push(position);
+                position = position.neighbour(side);
+            }
+        }
+        ring
+    }
+
+    /// The hexes along a straight line between the two, including both ends
+    pub fn line(&self, other: &HexCoordinate<T>) -> Vec<HexCoordinate<T>> {
+        let steps = self
+            .distance(other)
+            .to_i64()
+            .expect("Distance must fit in an i64");
+        if steps == 0 {
+            return vec![*self];
+        }
+        let to_f64 = |x: T| x.to_f64().expect("Coordinates must fit in an f64");
+        // Nudge off the start so points exactly between two hexes always round the same way
+        let (q0, r0) = (to_f64(self.q) + 1e-6, to_f64(self.r) + 1e-6);
+        let (q1, r1) = (to_f64(other.q), to_f64(other.r));
+        (0..=steps)
+            .map(|i| {
+                let t = i as f64 / steps as f64;
+                cube_round(q0 + (q1 - q0) * t, r0 + (r1 - r0) * t)
+            })
+            .collect_vec()
+    }
+}
+
+// Round each cube coordinate, then fix up whichever moved furthest so they still sum to 0
+fn cube_round<T: PrimInt + Signed>(q: f64, r: f64) -> HexCoordinate<T> {
+    let s = -q - r;
+    let (mut rq, mut rr, rs) = (q.round(), r.round(), s.round());
+    let (dq, dr, ds) = ((rq - q).abs(), (rr - r).abs(), (rs - s).abs());
+    if dq > dr && dq > ds {
+        rq = -rr - rs;
+    } else if dr > ds {
+        rr = -rq - rs;
+    }
+    HexCoordinate::new(
+        T::from(rq).expect("Rounded coordinate must fit in T"),
+        T::from(rr).expect("Rounded coordinate must fit in T"),
+    )
+}
+
+/// A hexagon shaped region of every hex within `radius` steps of `centre`
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq)]
+pub struct HexBounds<T: PrimInt + Signed> {
+    pub centre: HexCoordinate<T>,
+    pub radius: T,
+}
+
+impl<T: PrimInt + Signed> HexBounds<T> {
+    pub fn contains(&self, hex: &HexCoordinate<T>) -> bool {
+        self.centre.distance(hex) <= self.radius
+    }
+}
+
+impl<T: PrimInt + Signed> Neighbours for HexCoordinate<T> {
+    type Bounds = HexBounds<T>;
+
+    fn neighbours(&self) -> Vec<Self> {
+        self.get_adjacent_points()
+    }
+
+    fn neighbours_within(&self, bounds: &HexBounds<T>) -> Vec<Self> {
+        self.get_adjacent_points()
+            .into_iter()
+            .filter(|hex| bounds.contains(hex))
+            .collect_vec()
+    }
+}
+
+impl<T: PrimInt + Signed> Add for HexCoordinate<T> {
+    type Output = HexCoordinate<T>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        HexCoordinate {
+            q: self.q + rhs.q,
+            r: self.r + rhs.r,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Sub for HexCoordinate<T> {
+    type Output = HexCoordinate<T>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        HexCoordinate {
+            q: self.q - rhs.q,
+            r: self.r - rhs.r,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> AddAssign for HexCoordinate<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T: PrimInt + Signed> SubAssign for HexCoordinate<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        *self = *self - rhs;
+    }
+}
+
+impl<T: PrimInt + Signed> Mul<T> for HexCoordinate<T> {
+    type Output = HexCoordinate<T>;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        HexCoordinate {
+            q: self.q * rhs,
+            r: self.r * rhs,
+        }
+    }
+}
+
+impl<T: PrimInt + Signed> Neg for HexCoordinate<T> {
+    type Output = HexCoordinate<T>;
+
+    fn neg(self) -> Self::Output {
+        HexCoordinate {
+            q: -self.q,
+            r: -self.r,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::hash::Hash;
+
+    use super::*;
+    use crate::coordinate::{BoundingBox, Coordinate};
+
+    #[test]
+    fn parse_and_walk() -> color_eyre::Result<()> {
+        // Examples from 2017 day 11
+        for (input, expected) in [
+            ("ne,ne,ne", 3),
+            ("ne,ne,sw,sw", 0),
+            ("ne,ne,s,s", 2),
+            ("se,sw,se,sw,sw", 3),
+        ] {
+            let (rest, directions) = parse_directions(input)?;
+            assert_eq!(rest, "");
+            let origin = HexCoordinate::<i32>::default();
+            assert_eq!(origin.walk(&directions).distance(&origin), expected);
+        }
+        assert_eq!("nw".parse::<HexDirection>()?, HexDirection::NW);
+        assert!("x".parse::<HexDirection>().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn neighbours_and_directions() {
+        let centre = HexCoordinate::new(2i64, -1);
+        let adjacent = centre.get_adjacent_points();
+        assert_eq!(adjacent.len(), 6);
+        assert!(adjacent.iter().all(|n| n.distance(&centre) == 1));
+        for direction in HexDirection::ALL {
+            assert_eq!(
+                centre.neighbour(direction).neighbour(direction.opposite()),
+                centre
+            );
+        }
+    }
+
+    // A flood fill which only knows about the Neighbours trait
+    fn reachable<N: Neighbours + Copy + Hash + Eq>(start: N, bounds: &N::Bounds) -> usize {
+        let mut seen = HashSet::from([start]);
+        let mut queue = vec![start];
+        while let Some(current) = queue.pop() {
+            for next in current.neighbours_within(bounds) {
+                if seen.insert(next) {
+                    queue.push(next);
+                }
+            }
+        }
+        seen.len()
+    }
+
+    #[test]
+    fn flood_fill_square_and_hex() {
+        let square = BoundingBox::from_size(3usize, 4);
+        assert_eq!(reachable(Coordinate::new(1, 1), &square), 12);
+        let hexagon = HexBounds {
+            centre: HexCoordinate::new(1i32, 1),
+            radius: 2,
+        };
+        assert_eq!(reachable(HexCoordinate::new(1, 1), &hexagon), 19);
+        assert_eq!(HexCoordinate::new(0i64, 0).neighbours().len(), 6);
+    }
+
+    #[test]
+    fn rings() {
+        let centre = HexCoordinate::new(0i32, 0);
+        assert_eq!(centre.ring(0), vec![centre]);
+        assert_eq!(centre.ring(1), centre.get_adjacent_points());
+        let ring = centre.ring(3);
+        assert_eq!(ring.len(), 18);
+        assert!(ring.iter().all(|h| h.distance(&centre) == 3));
+        assert_eq!(ring.iter().unique().count(), 18);
+    }
+
+    #[test]
+    fn lines() {
+        let start = HexCoordinate::new(0i32, 0);
+        let end = HexCoordinate::new(3, -1);
+        let line = start.line(&end);
+        assert_eq!(line.len(), 4);
+        assert_eq!((line[0], line[3]), (start, end));
+        assert!(line.iter().tuple_windows().all(|(a, b)| a.distance(b) == 1));
+        assert_eq!(start.line(&start), vec![start]);
+    }
+}
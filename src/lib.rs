@@ -1,15 +1,31 @@
 pub mod coordinate;
+pub mod cycle;
+pub mod graph;
+pub mod hex;
+pub mod math;
+pub mod matrix;
 pub mod parsing;
+pub mod point;
 pub mod range;
+pub mod render;
+pub mod simulate;
+pub mod sparse_grid;
+pub mod svg;
+#[cfg(feature = "visualise")]
+pub mod visualise;
 // Force template to compile
 mod template;
 use std::fmt::Display;
 use std::fs::File;
+use std::io::BufRead;
 use std::io::BufReader;
-use std::io::{BufRead, Lines};
+use std::str::FromStr;
 
-use itertools::Itertools;
+use color_eyre::eyre::{eyre, Result, WrapErr};
+
+use matrix::Matrix;
 use reformation::Reformation;
+use render::Rulers;
 
 // Example union input
 #[derive(Reformation, Eq, PartialEq, Debug)]
@@ -50,18 +66,69 @@ pub fn group_file_by_empty_lines(reader: BufReader<File>) -> Vec<Vec<String>> {
         })
 }
 
-// Create a method for parsing a line of ints
-pub fn parse_line_to_num(line: &str) -> Vec<i32> {
+/// Split input into blocks separated by blank lines. Whitespace-only lines count as blank, and
+/// each block is trimmed of its trailing whitespace, so `\r\n` input behaves like `\n` input
+pub fn split_blocks(input: &str) -> Vec<&str> {
+    let mut blocks = vec![];
+    let mut block: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    for line in input.split_inclusive('\n') {
+        if line.trim().is_empty() {
+            if let Some((start, end)) = block.take() {
+                blocks.push(&input[start..end]);
+            }
+        } else {
+            let end = offset + line.trim_end().len();
+            block = Some((block.map_or(offset, |(start, _)| start), end));
+        }
+        offset += line.len();
+    }
+    if let Some((start, end)) = block {
+        blocks.push(&input[start..end]);
+    }
+    blocks
+}
+
+// Parse a line of whitespace separated numbers
+pub fn parse_line_to_num<T: FromStr>(line: &str) -> Result<Vec<T>>
+where
+    T::Err: Display,
+{
     line.split_whitespace()
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect_vec()
+        .map(|s| {
+            s.parse::<T>()
+                .map_err(|e| eyre!("Failed to parse {:?}: {}", s, e))
+        })
+        .collect()
 }
 
-// Create a method for parsing lines of a file to ints
-pub fn parse_lines_to_nums(lines: Lines<BufReader<File>>) -> Vec<i32> {
-    lines
-        .map(|line| line.unwrap().parse::<i32>().unwrap())
-        .collect_vec()
+// Lazily parse each line of a reader to a number, without collecting the whole input
+pub fn iter_lines_to_nums<T: FromStr, R: BufRead>(reader: R) -> impl Iterator<Item = Result<T>>
+where
+    T::Err: Display,
+{
+    reader.lines().enumerate().map(|(index, line)| {
+        let line = line.wrap_err_with(|| format!("Failed to read line {}", index + 1))?;
+        line.trim()
+            .parse::<T>()
+            .map_err(|e| eyre!("Failed to parse line {} {:?}: {}", index + 1, line, e))
+    })
+}
+
+// Parse each line of a reader to a number
+pub fn parse_lines_to_nums<T: FromStr, R: BufRead>(reader: R) -> Result<Vec<T>>
+where
+    T::Err: Display,
+{
+    iter_lines_to_nums(reader).collect()
+}
+
+// Parse each line of a string to a number
+pub fn parse_str_to_nums<T: FromStr>(input: &str) -> Result<Vec<T>>
+where
+    T::Err: Display,
+{
+    parse_lines_to_nums(input.as_bytes())
 }
 
 // #[allow(dead_code)]
@@ -78,22 +145,68 @@ pub fn parse_lines_to_nums(lines: Lines<BufReader<File>>) -> Vec<i32> {
 //         .collect_vec()
 // }
 
-pub fn transpose<T: Copy>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
-    matrix
-        .iter()
-        .flat_map(|row| row.iter().enumerate())
-        .fold(Vec::new(), |mut acc, (col, x)| {
-            if acc.len() <= col {
-                acc.push(Vec::new());
-            }
-            acc[col].push(*x);
-            acc
-        })
+// Panics if the rows aren't all the same length. Use matrix::MatrixView::transpose to avoid copying
+pub fn transpose<T: Clone>(matrix: &[Vec<T>]) -> Vec<Vec<T>> {
+    Matrix::try_from(matrix)
+        .expect("Can only transpose a rectangular matrix")
+        .view()
+        .transpose()
+        .to_vec()
 }
 
 pub fn print_matrix<T: Display>(matrix: &[Vec<T>]) {
-    for line in matrix {
-        println!("{}", line.iter().join(""));
+    println!(
+        "{}",
+        render::render_matrix(matrix, |x| x.to_string(), Rulers::default())
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_line_to_num_generic() -> Result<()> {
+        assert_eq!(parse_line_to_num::<i64>(" 1 -2\t3 ")?, vec![1, -2, 3]);
+        assert_eq!(parse_line_to_num::<f64>("1.5 2")?, vec![1.5, 2.0]);
+        assert!(parse_line_to_num::<u8>("1 300").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_str_to_nums_crlf() -> Result<()> {
+        assert_eq!(parse_str_to_nums::<u64>("10\r\n20\r\n")?, vec![10, 20]);
+        Ok(())
+    }
+
+    #[test]
+    fn parse_lines_to_nums_reports_line() {
+        let error = parse_lines_to_nums::<i32, _>("1\n2\nthree\n".as_bytes()).unwrap_err();
+        assert!(error.to_string().contains("line 3"));
+    }
+
+    #[test]
+    fn iter_lines_to_nums_is_lazy() -> Result<()> {
+        let mut iter = iter_lines_to_nums::<u32, _>("1\nbad\n".as_bytes());
+        assert_eq!(iter.next().transpose()?, Some(1));
+        assert!(iter.next().unwrap().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn split_blocks_lf() {
+        let blocks = split_blocks("a\nb\n\nc\n\n\nd\n");
+        assert_eq!(blocks, vec!["a\nb", "c", "d"]);
+    }
+
+    #[test]
+    fn split_blocks_crlf() {
+        let blocks = split_blocks("\r\na\r\nb  \r\n \r\nc\r\n\r\n");
+        assert_eq!(blocks, vec!["a\r\nb", "c"]);
+    }
+
+    #[test]
+    fn split_blocks_empty() {
+        assert!(split_blocks("\n \n").is_empty());
     }
-    println!();
 }
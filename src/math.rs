@@ -0,0 +1,134 @@
+use num::{BigInt, CheckedMul, CheckedSub, Integer, Signed};
+
+/// The gcd of every number, or 0 if there are none. Works for any integer, including `BigInt`
+pub fn gcd_all<T: Integer, I: IntoIterator<Item = T>>(numbers: I) -> T {
+    numbers.into_iter().fold(T::zero(), |acc, x| acc.gcd(&x))
+}
+
+/// The lcm of every number, or 1 if there are none. Panics on overflow in debug builds,
+/// see `checked_lcm_all` to handle it
+pub fn lcm_all<T: Integer, I: IntoIterator<Item = T>>(numbers: I) -> T {
+    numbers.into_iter().fold(T::one(), |acc, x| acc.lcm(&x))
+}
+
+/// The lcm of two numbers, or None if it overflows T
+pub fn checked_lcm<T: Integer + Clone + CheckedMul + CheckedSub>(a: &T, b: &T) -> Option<T> {
+    if a.is_zero() || b.is_zero() {
+        return Some(T::zero());
+    }
+    // Divide first, so only the result itself can overflow
+    let lcm = (a.clone() / a.gcd(b)).checked_mul(b)?;
+    // Integer has no abs, but lcm should never be negative. Negating MIN overflows too
+    if lcm < T::zero() {
+        T::zero().checked_sub(&lcm)
+    } else {
+        Some(lcm)
+    }
+}
+
+/// As `lcm_all`, but None if any step overflows
+pub fn checked_lcm_all<T: Integer + Clone + CheckedMul + CheckedSub, I: IntoIterator<Item = T>>(
+    numbers: I,
+) -> Option<T> {
+    numbers
+        .into_iter()
+        .try_fold(T::one(), |acc, x| checked_lcm(&acc, &x))
+}
+
+/// Returns (g, x, y) where g = gcd(a, b) and a * x + b * y = g
+pub fn extended_gcd<T: Integer + Signed + Clone>(a: &T, b: &T) -> (T, T, T) {
+    let result = a.extended_gcd(b);
+    (result.gcd, result.x, result.y)
+}
+
+/// The x in 0..m where a * x = 1 (mod m), or None if a and m aren't coprime
+pub fn mod_inverse<T: Integer + Signed + Clone>(a: &T, m: &T) -> Option<T> {
+    let (g, x, _) = extended_gcd(&a.mod_floor(m), m);
+    g.is_one().then(|| x.mod_floor(m))
+}
+
+/// Solve x = a (mod m) for every (a, m), returning the smallest non-negative x and the
+/// modulus it repeats with. The moduli don't need to be coprime. Returns None if there's no
+/// solution, or if the answer doesn't fit in T.
+/// Works in `BigInt` internally, so unsigned types like `u128` are fine
+pub fn crt<T, I>(congruences: I) -> Option<(T, T)>
+where
+    T: Integer + Into<BigInt> + TryFrom<BigInt>,
+    I: IntoIterator<Item = (T, T)>,
+{
+    let (x, modulus) = congruences.into_iter().try_fold(
+        (BigInt::from(0), BigInt::from(1)),
+        |(a1, m1), (a2, m2)| {
+            let (a2, m2): (BigInt, BigInt) = (a2.into(), m2.into());
+            let a2 = a2.mod_floor(&m2);
+            let g = m1.gcd(&m2);
+            let difference = a2 - &a1;
+            if !difference.is_multiple_of(&g) {
+                return None;
+            }
+            // Solve a1 + m1 * k = a2 (mod m2) for k, working modulo m2 / g
+            let reduced = &m2 / &g;
+            let inverse = mod_inverse(&(&m1 / &g), &reduced)?;
+            let k = ((difference / g).mod_floor(&reduced) * inverse).mod_floor(&reduced);
+            let modulus = m1.lcm(&m2);
+            let x = (a1 + m1 * k).mod_floor(&modulus);
+            Some((x, modulus))
+        },
+    )?;
+    Some((T::try_from(x).ok()?, T::try_from(modulus).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use num::BigInt;
+
+    use super::*;
+
+    #[test]
+    fn gcd_and_lcm() {
+        assert_eq!(gcd_all(vec![12, 18, 30]), 6);
+        assert_eq!(gcd_all(Vec::<u32>::new()), 0);
+        assert_eq!(lcm_all(vec![2u128, 3, 4]), 12);
+        assert_eq!(lcm_all(Vec::<i32>::new()), 1);
+        assert_eq!(checked_lcm_all(vec![4u8, 6, 10]), Some(60));
+        assert_eq!(checked_lcm_all(vec![16u8, 17]), None);
+        assert_eq!(checked_lcm(&-4, &6), Some(12));
+        assert_eq!(checked_lcm(&i64::MIN, &1), None);
+        assert_eq!(
+            checked_lcm_all(vec![u64::MAX as u128, 2]),
+            Some(2 * u64::MAX as u128)
+        );
+    }
+
+    #[test]
+    fn inverses() {
+        let (g, x, y) = extended_gcd(&240, &46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(&3, &11), Some(4));
+        assert_eq!(mod_inverse(&-3, &11), Some(7));
+        assert_eq!(mod_inverse(&6, &9), None);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(vec![(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Moduli which share factors
+        assert_eq!(crt(vec![(2i64, 6), (8, 10)]), Some((8, 30)));
+        assert_eq!(crt(vec![(1i64, 6), (2, 10)]), None);
+        assert_eq!(crt(Vec::<(i32, i32)>::new()), Some((0, 1)));
+        // Too big for an i64
+        assert_eq!(crt(vec![(1i64, i64::MAX), (0, 2)]), None);
+        let max = u64::MAX as u128;
+        assert_eq!(crt(vec![(1u128, max), (0, 2)]), Some((max + 1, max * 2)));
+        assert_eq!(crt(vec![(1u128, u128::MAX), (0, 2)]), None);
+        let big = BigInt::from(i64::MAX);
+        assert_eq!(
+            crt(vec![
+                (BigInt::from(1), big.clone()),
+                (BigInt::from(0), BigInt::from(2))
+            ]),
+            Some((big.clone() + 1, big * 2))
+        );
+    }
+}
@@ -0,0 +1,529 @@
+use std::fmt::{self, Display};
+use std::mem;
+use std::ops::{Index, IndexMut};
+
+use color_eyre::eyre::{eyre, Result};
+
+use itertools::Itertools;
+
+use crate::coordinate::{BoundingBox, IndexingCoordinate};
+use crate::render::{self, Rulers};
+
+/// A dense, rectangular grid stored in row-major order
+#[derive(Clone, Debug, Default, Hash, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    cols: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Build a matrix from a list of rows, failing if they aren't all the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Matrix<T>> {
+        let cols = rows.first().map_or(0, |row| row.len());
+        if let Some((index, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != cols) {
+            return Err(eyre!(
+                "Row {} has length {}, expected {}",
+                index,
+                row.len(),
+                cols
+            ));
+        }
+        Ok(Matrix {
+            rows: rows.len(),
+            cols,
+            data: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    pub fn from_fn<F: FnMut(IndexingCoordinate) -> T>(
+        rows: usize,
+        cols: usize,
+        mut f: F,
+    ) -> Matrix<T> {
+        let data = (0..rows)
+            .flat_map(|row| (0..cols).map(move |col| IndexingCoordinate { row, col }))
+            .map(&mut f)
+            .collect();
+        Matrix { rows, cols, data }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    fn offset(&self, coordinate: IndexingCoordinate) -> Option<usize> {
+        if coordinate.row < self.rows && coordinate.col < self.cols {
+            Some(coordinate.row * self.cols + coordinate.col)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, coordinate: IndexingCoordinate) -> Option<&T> {
+        self.offset(coordinate).map(|offset| &self.data[offset])
+    }
+
+    pub fn get_mut(&mut self, coordinate: IndexingCoordinate) -> Option<&mut T> {
+        self.offset(coordinate).map(|offset| &mut self.data[offset])
+    }
+
+    /// Replace the value at a coordinate, returning the old value. Panics if out of bounds
+    pub fn set(&mut self, coordinate: IndexingCoordinate, value: T) -> T {
+        mem::replace(&mut self[coordinate], value)
+    }
+
+    pub fn row(&self, row: usize) -> &[T] {
+        &self.data[row * self.cols..(row + 1) * self.cols]
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a size of 0, and there are no elements to iterate anyway
+        self.data.chunks(self.cols.max(1))
+    }
+
+    /// The coordinates covered by the matrix, or None if it's empty
+    pub fn bounds(&self) -> Option<BoundingBox<usize>> {
+        (self.rows > 0 && self.cols > 0).then(|| BoundingBox::from_size(self.rows, self.cols))
+    }
+
+    /// The up to 4 orthogonal neighbours of a coordinate, with their values
+    pub fn neighbours(&self, coordinate: IndexingCoordinate) -> Vec<(IndexingCoordinate, &T)> {
+        self.bounds().map_or_else(Vec::new, |bounds| {
+            self.with_values(coordinate.get_adjacent_points(&bounds))
+        })
+    }
+
+    /// The up to 8 neighbours of a coordinate including diagonals, with their values
+    pub fn neighbours_diagonal(
+        &self,
+        coordinate: IndexingCoordinate,
+    ) -> Vec<(IndexingCoordinate, &T)> {
+        self.bounds().map_or_else(Vec::new, |bounds| {
+            self.with_values(coordinate.get_adjacent_points_diagonal(&bounds))
+        })
+    }
+
+    fn with_values(&self, coordinates: Vec<IndexingCoordinate>) -> Vec<(IndexingCoordinate, &T)> {
+        coordinates
+            .into_iter()
+            .filter_map(|c| self.get(c).map(|value| (c, value)))
+            .collect_vec()
+    }
+
+    /// Draw each cell with `cell`, which should be a single character wide if using rulers
+    pub fn render<C: Display>(&self, cell: impl Fn(&T) -> C, rulers: Rulers) -> String {
+        render::render(|w| {
+            render::write_cells(
+                w,
+                &(0..self.rows).map(|i| i.to_string()).collect_vec(),
+                &(0..self.cols).map(|i| i.to_string()).collect_vec(),
+                |row, col| cell(&self[IndexingCoordinate { row, col }]),
+                rulers,
+            )
+        })
+    }
+
+    /// Borrow the whole matrix as a view, which can then be transformed without copying
+    pub fn view(&self) -> MatrixView<'_, T> {
+        MatrixView {
+            matrix: self,
+            origin: IndexingCoordinate::default(),
+            rows: self.rows,
+            cols: self.cols,
+            transposed: false,
+            flip_rows: false,
+            flip_cols: false,
+        }
+    }
+}
+
+impl<T> TryFrom<Vec<Vec<T>>> for Matrix<T> {
+    type Error = color_eyre::Report;
+
+    fn try_from(rows: Vec<Vec<T>>) -> Result<Self> {
+        Matrix::from_rows(rows)
+    }
+}
+
+impl<T: Clone> TryFrom<&[Vec<T>]> for Matrix<T> {
+    type Error = color_eyre::Report;
+
+    fn try_from(rows: &[Vec<T>]) -> Result<Self> {
+        Matrix::from_rows(rows.to_vec())
+    }
+}
+
+impl<T: Display> Display for Matrix<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.view().fmt(f)
+    }
+}
+
+impl<T> Index<IndexingCoordinate> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, coordinate: IndexingCoordinate) -> &Self::Output {
+        self.get(coordinate).unwrap_or_else(|| {
+            panic!(
+                "{:?} out of bounds for {}x{} matrix",
+                coordinate, self.rows, self.cols
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<IndexingCoordinate> for Matrix<T> {
+    fn index_mut(&mut self, coordinate: IndexingCoordinate) -> &mut Self::Output {
+        let (rows, cols) = (self.rows, self.cols);
+        self.get_mut(coordinate).unwrap_or_else(|| {
+            panic!(
+                "{:?} out of bounds for {}x{} matrix",
+                coordinate, rows, cols
+            )
+        })
+    }
+}
+
+/// A borrowed window onto a `Matrix`, which may be transposed, flipped or rotated.
+/// Transformations only change how coordinates are mapped, the underlying data is never copied
+#[derive(Debug)]
+pub struct MatrixView<'a, T> {
+    matrix: &'a Matrix<T>,
+    // Top left of the window in the underlying matrix
+    origin: IndexingCoordinate,
+    // Dimensions as seen through the view
+    rows: usize,
+    cols: usize,
+    // Flips are applied in view space, before transposing into the underlying matrix
+    transposed: bool,
+    flip_rows: bool,
+    flip_cols: bool,
+}
+
+impl<'a, T> Clone for MatrixView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for MatrixView<'a, T> {}
+
+impl<'a, T> MatrixView<'a, T> {
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn cols(&self) -> usize {
+        self.cols
+    }
+
+    // Map an in-bounds view coordinate to the underlying matrix
+    fn underlying(&self, coordinate: IndexingCoordinate) -> IndexingCoordinate {
+        let row = if self.flip_rows {
+            self.rows - 1 - coordinate.row
+        } else {
+            coordinate.row
+        };
+        let col = if self.flip_cols {
+            self.cols - 1 - coordinate.col
+        } else {
+            coordinate.col
+        };
+        let offset = if self.transposed {
+            IndexingCoordinate { row: col, col: row }
+        } else {
+            IndexingCoordinate { row, col }
+        };
+        self.origin + offset
+    }
+
+    pub fn get(&self, coordinate: IndexingCoordinate) -> Option<&'a T> {
+        if coordinate.row < self.rows && coordinate.col < self.cols {
+            self.matrix.get(self.underlying(coordinate))
+        } else {
+            None
+        }
+    }
+
+    fn at(&self, coordinate: IndexingCoordinate) -> &'a T {
+        self.get(coordinate).unwrap_or_else(|| {
+            panic!(
+                "{:?} out of bounds for {}x{} view",
+                coordinate, self.rows, self.cols
+            )
+        })
+    }
+
+    pub fn transpose(self) -> Self {
+        MatrixView {
+            rows: self.cols,
+            cols: self.rows,
+            transposed: !self.transposed,
+            flip_rows: self.flip_cols,
+            flip_cols: self.flip_rows,
+            ..self
+        }
+    }
+
+    /// Mirror left to right
+    pub fn flip_horizontal(self) -> Self {
+        MatrixView {
+            flip_cols: !self.flip_cols,
+            ..self
+        }
+    }
+
+    /// Mirror top to bottom
+    pub fn flip_vertical(self) -> Self {
+        MatrixView {
+            flip_rows: !self.flip_rows,
+            ..self
+        }
+    }
+
+    /// Rotate 90° clockwise
+    pub fn rotate_cw(self) -> Self {
+        self.transpose().flip_horizontal()
+    }
+
+    pub fn rotate_180(self) -> Self {
+        self.flip_horizontal().flip_vertical()
+    }
+
+    /// Rotate 90° anticlockwise, equivalent to 270° clockwise
+    pub fn rotate_ccw(self) -> Self {
+        self.transpose().flip_vertical()
+    }
+
+    /// Restrict the view to `rows` x `cols` cells starting at `top_left`.
+    /// Returns None if the sub-view doesn't fit
+    pub fn sub_view(self, top_left: IndexingCoordinate, rows: usize, cols: usize) -> Option<Self> {
+        if top_left.row + rows > self.rows || top_left.col + cols > self.cols {
+            return None;
+        }
+        if rows == 0 || cols == 0 {
+            return Some(MatrixView { rows, cols, ..self });
+        }
+        let a = self.underlying(top_left);
+        let b = self.underlying(top_left + IndexingCoordinate::new(rows - 1, cols - 1));
+        Some(MatrixView {
+            origin: IndexingCoordinate::new(a.row.min(b.row), a.col.min(b.col)),
+            rows,
+            cols,
+            ..self
+        })
+    }
+
+    pub fn row(&self, row: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.cols).map(move |col| view.at(IndexingCoordinate { row, col }))
+    }
+
+    pub fn col(&self, col: usize) -> impl Iterator<Item = &'a T> {
+        let view = *self;
+        (0..self.rows).map(move |row| view.at(IndexingCoordinate { row, col }))
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.rows).map(move |row| view.row(row))
+    }
+
+    pub fn iter_cols(&self) -> impl Iterator<Item = impl Iterator<Item = &'a T>> {
+        let view = *self;
+        (0..self.cols).map(move |col| view.col(col))
+    }
+}
+
+impl<'a, T: Clone> MatrixView<'a, T> {
+    pub fn to_vec(&self) -> Vec<Vec<T>> {
+        self.iter_rows().map(|row| row.cloned().collect()).collect()
+    }
+
+    pub fn to_matrix(&self) -> Matrix<T> {
+        Matrix::from_fn(self.rows, self.cols, |c| self[c].clone())
+    }
+}
+
+impl<'a, T: Display> Display for MatrixView<'a, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for row in self.iter_rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, T> Index<IndexingCoordinate> for MatrixView<'a, T> {
+    type Output = T;
+
+    fn index(&self, coordinate: IndexingCoordinate) -> &Self::Output {
+        self.at(coordinate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_by_three() -> Matrix<i32> {
+        Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap()
+    }
+
+    #[test]
+    fn from_rows_ragged() {
+        assert!(Matrix::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn index_non_square() {
+        let matrix = two_by_three();
+        assert_eq!((matrix.rows(), matrix.cols()), (2, 3));
+        assert_eq!(matrix[IndexingCoordinate::new(1, 2)], 6);
+        assert_eq!(matrix.get(IndexingCoordinate::new(2, 0)), None);
+        assert_eq!(matrix.row(1), &[4, 5, 6]);
+    }
+
+    #[test]
+    fn set_value() {
+        let mut matrix = two_by_three();
+        assert_eq!(matrix.set(IndexingCoordinate::new(0, 1), 9), 2);
+        assert_eq!(matrix.row(0), &[1, 9, 3]);
+    }
+
+    #[test]
+    fn transpose_non_square() {
+        let matrix = two_by_three();
+        let view = matrix.view().transpose();
+        assert_eq!((view.rows(), view.cols()), (3, 2));
+        assert_eq!(view.to_vec(), vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+        assert_eq!(view.transpose().to_matrix(), matrix);
+    }
+
+    #[test]
+    fn rotate_non_square() {
+        let matrix = two_by_three();
+        assert_eq!(
+            matrix.view().rotate_cw().to_vec(),
+            vec![vec![4, 1], vec![5, 2], vec![6, 3]]
+        );
+        assert_eq!(
+            matrix.view().rotate_180().to_vec(),
+            vec![vec![6, 5, 4], vec![3, 2, 1]]
+        );
+        assert_eq!(
+            matrix.view().rotate_ccw().to_vec(),
+            vec![vec![3, 6], vec![2, 5], vec![1, 4]]
+        );
+        assert_eq!(
+            matrix.view().rotate_cw().rotate_cw().rotate_cw().to_vec(),
+            matrix.view().rotate_ccw().to_vec()
+        );
+        assert_eq!(
+            matrix
+                .view()
+                .rotate_cw()
+                .rotate_cw()
+                .rotate_cw()
+                .rotate_cw()
+                .to_matrix(),
+            matrix
+        );
+    }
+
+    #[test]
+    fn flip_non_square() {
+        let matrix = two_by_three();
+        assert_eq!(
+            matrix.view().flip_horizontal().to_vec(),
+            vec![vec![3, 2, 1], vec![6, 5, 4]]
+        );
+        assert_eq!(
+            matrix.view().flip_vertical().to_vec(),
+            vec![vec![4, 5, 6], vec![1, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn sub_view_non_square() {
+        let matrix = Matrix::from_fn(3, 4, |c| c.row * 10 + c.col);
+        let view = matrix
+            .view()
+            .sub_view(IndexingCoordinate::new(1, 1), 2, 3)
+            .unwrap();
+        assert_eq!(view.to_vec(), vec![vec![11, 12, 13], vec![21, 22, 23]]);
+        assert!(matrix
+            .view()
+            .sub_view(IndexingCoordinate::new(2, 0), 2, 1)
+            .is_none());
+    }
+
+    #[test]
+    fn sub_view_of_rotation() {
+        let matrix = Matrix::from_fn(3, 4, |c| c.row * 10 + c.col);
+        // Rotated clockwise:
+        // 20 10 00
+        // 21 11 01
+        // 22 12 02
+        // 23 13 03
+        let view = matrix
+            .view()
+            .rotate_cw()
+            .sub_view(IndexingCoordinate::new(1, 0), 2, 2)
+            .unwrap();
+        assert_eq!(view.to_vec(), vec![vec![21, 11], vec![22, 12]]);
+        assert_eq!(view.rotate_ccw().to_vec(), vec![vec![11, 12], vec![21, 22]]);
+    }
+
+    #[test]
+    fn display_rotated() {
+        let matrix = two_by_three();
+        assert_eq!(matrix.to_string(), "123\n456\n");
+        assert_eq!(matrix.view().rotate_cw().to_string(), "41\n52\n63\n");
+    }
+
+    #[test]
+    fn iter_cols_non_square() {
+        let matrix = two_by_three();
+        let cols = matrix
+            .view()
+            .iter_cols()
+            .map(|col| col.copied().collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(cols, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+    }
+
+    #[test]
+    fn neighbours() -> color_eyre::Result<()> {
+        let matrix = Matrix::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]])?;
+        let corner = IndexingCoordinate::new(0, 2);
+        assert_eq!(
+            matrix.neighbours(corner),
+            vec![
+                (IndexingCoordinate::new(1, 2), &6),
+                (IndexingCoordinate::new(0, 1), &2)
+            ]
+        );
+        assert_eq!(matrix.neighbours_diagonal(corner).len(), 3);
+        assert_eq!(Matrix::<i32>::default().bounds(), None);
+        Ok(())
+    }
+
+    #[test]
+    fn render_with_rulers() -> color_eyre::Result<()> {
+        let matrix = Matrix::from_rows(vec![vec![true, false], vec![false, true]])?;
+        assert_eq!(
+            matrix.render(|&b| if b { '#' } else { '.' }, Rulers::both()),
+            "  01\n0 #.\n1 .#\n"
+        );
+        Ok(())
+    }
+}
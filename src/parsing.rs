@@ -1,24 +1,337 @@
-use std::str::FromStr;
+use std::collections::HashMap;
+use std::num::ParseIntError;
 
 use nom::{
-    character::complete::{char, digit1, space1},
-    combinator::{map_res, opt},
-    multi::separated_list1,
-    sequence::pair,
-    IResult,
+    branch::alt,
+    bytes::complete::{tag, take, take_till1},
+    character::complete::{char, digit1, line_ending, multispace0, one_of, space0, space1},
+    combinator::{all_consuming, map, map_parser, opt, recognize, verify},
+    error::{Error, ErrorKind},
+    multi::{many1_count, separated_list1},
+    sequence::{delimited, pair, preceded, terminated},
+    IResult, Parser,
 };
+use num::{BigInt, BigUint};
 
-pub fn parse_number<T: FromStr>(input: &str) -> IResult<&str, T> {
-    map_res(
-        pair(opt(char('-')), digit1),
-        |(sign, x): (_, &str)| match sign {
-            Some(_) => ("-".to_string() + x).parse::<T>(),
-            None => x.parse::<T>(),
-        },
-    )(input)
+/// Reasons a well-formed number can't be represented by the requested type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberError {
+    /// The value is too large or too small for the type
+    Overflow,
+    /// A negative value was given for an unsigned type
+    SignMismatch,
+}
+
+impl NumberError {
+    /// The nom error kind reported by `parse_number`
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            NumberError::Overflow => ErrorKind::TooLarge,
+            NumberError::SignMismatch => ErrorKind::Verify,
+        }
+    }
+}
+
+/// Types that `parse_number` can produce
+pub trait FromDigits: Sized {
+    /// Convert an optional `-` followed by one or more ascii digits. Only
+    /// `parse_signed_number` passes a leading `+`
+    fn from_digits(digits: &str) -> Result<Self, NumberError>;
+}
+
+macro_rules! impl_from_digits_signed {
+    ($($t:ty),*) => {
+        $(
+            impl FromDigits for $t {
+                fn from_digits(digits: &str) -> Result<Self, NumberError> {
+                    digits.parse().map_err(|_: ParseIntError| NumberError::Overflow)
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_digits_unsigned {
+    ($($t:ty),*) => {
+        $(
+            impl FromDigits for $t {
+                fn from_digits(digits: &str) -> Result<Self, NumberError> {
+                    if digits.starts_with('-') {
+                        return Err(NumberError::SignMismatch);
+                    }
+                    digits.parse().map_err(|_: ParseIntError| NumberError::Overflow)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_digits_signed!(i8, i16, i32, i64, i128, isize);
+impl_from_digits_unsigned!(u8, u16, u32, u64, u128, usize);
+
+impl FromDigits for BigInt {
+    fn from_digits(digits: &str) -> Result<Self, NumberError> {
+        Ok(digits.parse().expect("digits are validated by the parser"))
+    }
+}
+
+impl FromDigits for BigUint {
+    fn from_digits(digits: &str) -> Result<Self, NumberError> {
+        if digits.starts_with('-') {
+            return Err(NumberError::SignMismatch);
+        }
+        Ok(digits.parse().expect("digits are validated by the parser"))
+    }
+}
+
+/// Parse a number with an optional leading `-`. A `+` is left alone, since some grids use it
+/// as a symbol right next to a number, see `parse_signed_number` for explicitly signed numbers.
+/// Overflow fails with `ErrorKind::TooLarge`, and a negative unsigned value with `ErrorKind::Verify`
+pub fn parse_number<T: FromDigits>(input: &str) -> IResult<&str, T> {
+    let (rest, digits) = recognize(pair(opt(char('-')), digit1))(input)?;
+    from_digits(input, rest, digits)
+}
+
+/// As `parse_number`, but also accepting a leading `+`
+pub fn parse_signed_number<T: FromDigits>(input: &str) -> IResult<&str, T> {
+    let (rest, digits) = recognize(pair(opt(one_of("+-")), digit1))(input)?;
+    from_digits(input, rest, digits)
+}
+
+fn from_digits<'a, T: FromDigits>(
+    input: &'a str,
+    rest: &'a str,
+    digits: &str,
+) -> IResult<&'a str, T> {
+    match T::from_digits(digits) {
+        Ok(number) => Ok((rest, number)),
+        Err(e) => Err(nom::Err::Error(Error::new(input, e.kind()))),
+    }
 }
 
 /// Parse a list of whitespace separated numbers
-pub fn parse_numbers<T: FromStr>(input: &str) -> IResult<&str, Vec<T>> {
+pub fn parse_numbers<T: FromDigits>(input: &str) -> IResult<&str, Vec<T>> {
     separated_list1(space1, parse_number)(input)
 }
+
+/// Parse a list of whitespace separated numbers, which may have a `+` or `-` sign,
+/// allowing leading and trailing spaces
+pub fn padded_numbers<T: FromDigits>(input: &str) -> IResult<&str, Vec<T>> {
+    delimited(space0, separated_list1(space1, parse_signed_number), space0)(input)
+}
+
+/// Parse a number, which may have a `+` or `-` sign, occupying exactly `width` characters,
+/// padded with spaces on either side
+pub fn fixed_width<'a, T: FromDigits>(width: usize) -> impl FnMut(&'a str) -> IResult<&'a str, T> {
+    map_parser(
+        take(width),
+        all_consuming(delimited(space0, parse_signed_number, space0)),
+    )
+}
+
+/// Parse a list of numbers aligned in columns of `width` characters, separated by a single space
+pub fn aligned_numbers<'a, T: FromDigits>(
+    width: usize,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    separated_list1(char(' '), fixed_width(width))
+}
+
+/// Match the gap between two blocks: the end of the current line, including any trailing
+/// spaces, followed by one or more whitespace-only lines. Accepts both `\n` and `\r\n` line endings
+pub fn blank_lines(input: &str) -> IResult<&str, &str> {
+    recognize(pair(
+        pair(space0, line_ending),
+        many1_count(pair(space0, line_ending)),
+    ))(input)
+}
+
+/// Parse one or more blocks separated by blank lines, consuming any trailing whitespace
+pub fn blocks<'a, O, F>(parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<O>>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    terminated(separated_list1(blank_lines, parser), multispace0)
+}
+
+/// Parse a `label:` header, followed by either a single space or a line ending, returning the label
+pub fn label(input: &str) -> IResult<&str, &str> {
+    terminated(
+        take_till1(|c| c == ':' || c == '\r' || c == '\n'),
+        pair(char(':'), alt((line_ending, tag(" ")))),
+    )(input)
+}
+
+/// Parse the body of a section headed by `name:`
+pub fn labelled<'a, O, F>(name: &'a str, parser: F) -> impl FnMut(&'a str) -> IResult<&'a str, O>
+where
+    F: Parser<&'a str, O, Error<&'a str>>,
+{
+    preceded(verify(label, move |l: &str| l == name), parser)
+}
+
+/// Take everything up to the next blank line or the end of input, without trailing whitespace
+pub fn section_body(input: &str) -> IResult<&str, &str> {
+    let end = input
+        .char_indices()
+        .filter(|(_, c)| *c == '\r' || *c == '\n')
+        .map(|(i, _)| i)
+        .find(|&i| blank_lines(&input[i..]).is_ok())
+        .unwrap_or(input.len());
+    let body = input[..end].trim_end();
+    Ok((&input[body.len()..], body))
+}
+
+/// Parse a `label: body` or `label:\nbody` section, where the body runs until the next blank line
+pub fn labelled_section(input: &str) -> IResult<&str, (&str, &str)> {
+    pair(label, section_body)(input)
+}
+
+/// Parse blank line separated labelled sections, in the order they appear
+pub fn labelled_sections(input: &str) -> IResult<&str, Vec<(&str, &str)>> {
+    blocks(labelled_section)(input)
+}
+
+/// Parse blank line separated labelled sections into a map keyed by label
+pub fn labelled_section_map(input: &str) -> IResult<&str, HashMap<&str, &str>> {
+    map(labelled_sections, |sections| sections.into_iter().collect())(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nom::character::complete::alpha1;
+
+    #[test]
+    fn parse_number_signs() -> color_eyre::Result<()> {
+        assert_eq!(parse_number::<i32>("-12")?, ("", -12));
+        assert_eq!(parse_signed_number::<i32>("+12")?, ("", 12));
+        assert_eq!(parse_signed_number::<u32>("+12")?, ("", 12));
+        // A + is never consumed by parse_number, so it's left for the caller
+        assert_eq!(
+            parse_number::<u32>("+12"),
+            Err(nom::Err::Error(Error::new("+12", ErrorKind::Digit)))
+        );
+        assert_eq!(parse_number::<i32>("-12+3")?, ("+3", -12));
+        assert!(parse_number::<u32>("-12").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn parse_number_large_types() -> color_eyre::Result<()> {
+        assert_eq!(
+            parse_number::<u128>("340282366920938463463374607431768211455")?,
+            ("", u128::MAX)
+        );
+        assert_eq!(
+            parse_number::<i128>("-170141183460469231731687303715884105728")?,
+            ("", i128::MIN)
+        );
+        let digits = format!("-1{}", "0".repeat(42));
+        assert_eq!(
+            parse_number::<BigInt>(&digits),
+            Ok(("", -BigInt::from(10).pow(42)))
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn parse_number_error_kinds() {
+        assert_eq!(
+            parse_number::<u8>("256"),
+            Err(nom::Err::Error(Error::new("256", ErrorKind::TooLarge)))
+        );
+        assert_eq!(
+            parse_number::<i8>("-129"),
+            Err(nom::Err::Error(Error::new("-129", ErrorKind::TooLarge)))
+        );
+        assert_eq!(
+            parse_number::<u64>("-1"),
+            Err(nom::Err::Error(Error::new("-1", ErrorKind::Verify)))
+        );
+        assert_eq!(
+            parse_number::<BigUint>("-1"),
+            Err(nom::Err::Error(Error::new("-1", ErrorKind::Verify)))
+        );
+    }
+
+    #[test]
+    fn padded_numbers_leading_and_trailing() -> color_eyre::Result<()> {
+        let (rest, result) = padded_numbers::<i64>("   7  -15   +30  ")?;
+        assert_eq!(result, vec![7, -15, 30]);
+        assert_eq!(rest, "");
+        Ok(())
+    }
+
+    #[test]
+    fn aligned_numbers_right_aligned() -> color_eyre::Result<()> {
+        let (rest, result) = aligned_numbers::<u32>(2)(" 1 21  6 | 9")?;
+        assert_eq!(result, vec![1, 21, 6]);
+        assert_eq!(rest, " | 9");
+        Ok(())
+    }
+
+    #[test]
+    fn fixed_width_signed() -> color_eyre::Result<()> {
+        let (rest, result) = fixed_width::<i16>(4)(" -42+7")?;
+        assert_eq!(result, -42);
+        assert_eq!(fixed_width::<i16>(2)(rest)?, ("", 7));
+        assert!(fixed_width::<u8>(3)("1 2").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_lf() -> color_eyre::Result<()> {
+        let (rest, result) = blocks(alpha1)("a\n\nb\n\n\nc\n")?;
+        assert_eq!(result, vec!["a", "b", "c"]);
+        assert_eq!(rest, "");
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_crlf_and_whitespace() -> color_eyre::Result<()> {
+        let (rest, result) = blocks(alpha1)("a\r\n  \r\nb\r\n\r\n \t")?;
+        assert_eq!(result, vec!["a", "b"]);
+        assert_eq!(rest, "");
+        Ok(())
+    }
+
+    #[test]
+    fn blocks_trailing_spaces() -> color_eyre::Result<()> {
+        let (rest, result) = blocks(alpha1)("a  \n\nb \t\r\n \r\nc")?;
+        assert_eq!(result, vec!["a", "b", "c"]);
+        assert_eq!(rest, "");
+        Ok(())
+    }
+
+    #[test]
+    fn labelled_sections_inline_and_multiline() -> color_eyre::Result<()> {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n";
+        let (rest, result) = labelled_sections(input)?;
+        assert_eq!(
+            result,
+            vec![
+                ("seeds", "79 14"),
+                ("seed-to-soil map", "50 98 2\n52 50 48")
+            ]
+        );
+        assert_eq!(rest, "");
+        Ok(())
+    }
+
+    #[test]
+    fn labelled_section_map_lookup() -> color_eyre::Result<()> {
+        let input = "a: 1\r\n\r\nb:\r\n2\r\n3";
+        let (_, result) = labelled_section_map(input)?;
+        assert_eq!(result.get("a"), Some(&"1"));
+        assert_eq!(result.get("b"), Some(&"2\r\n3"));
+        Ok(())
+    }
+
+    #[test]
+    fn labelled_by_name() -> color_eyre::Result<()> {
+        let (_, result) = labelled("seeds", parse_numbers::<u32>)("seeds: 79 14")?;
+        assert_eq!(result, vec![79, 14]);
+        assert!(labelled("seeds", parse_numbers::<u32>)("soil: 79 14").is_err());
+        Ok(())
+    }
+}
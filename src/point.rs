@@ -0,0 +1,235 @@
+use std::ops::{Add, Index, IndexMut, Mul, Sub};
+
+use itertools::Itertools;
+use num::PrimInt;
+
+use crate::coordinate::{abs_diff, Coordinate};
+
+/// A point in N dimensional space, for puzzles that don't fit on a `Coordinate` grid
+#[derive(Clone, Copy, Debug, Hash, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Point<T: PrimInt, const N: usize>(pub [T; N]);
+
+/// A point in 3D space, accessed with `x()`, `y()` and `z()`
+pub type Coord3<T> = Point<T, 3>;
+
+impl<T: PrimInt, const N: usize> Point<T, N> {
+    pub fn new(values: [T; N]) -> Point<T, N> {
+        Point(values)
+    }
+
+    pub fn origin() -> Point<T, N> {
+        Point([T::zero(); N])
+    }
+
+    pub fn manhattan_distance(&self, other: &Point<T, N>) -> T {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(T::zero(), |acc, (&a, &b)| acc + abs_diff(a, b))
+    }
+
+    /// The largest distance along any one axis, i.e. the number of king moves between the points
+    pub fn chebyshev_distance(&self, other: &Point<T, N>) -> T {
+        self.0
+            .iter()
+            .zip(other.0.iter())
+            .fold(T::zero(), |acc, (&a, &b)| acc.max(abs_diff(a, b)))
+    }
+
+    // Apply an offset of -1, 0 or 1 per axis, or None if that would overflow
+    fn offset(&self, offsets: &[i8; N]) -> Option<Point<T, N>> {
+        let mut result = *self;
+        for (value, offset) in result.0.iter_mut().zip(offsets.iter()) {
+            *value = match offset {
+                -1 => value.checked_sub(&T::one())?,
+                1 => value.checked_add(&T::one())?,
+                _ => *value,
+            };
+        }
+        Some(result)
+    }
+
+    /// The 2N points one step away along a single axis. Points which would overflow T are skipped
+    pub fn get_adjacent_points(&self) -> Vec<Point<T, N>> {
+        (0..N)
+            .flat_map(|axis| {
+                [-1, 1].into_iter().filter_map(move |step| {
+                    let mut offsets = [0; N];
+                    offsets[axis] = step;
+                    self.offset(&offsets)
+                })
+            })
+            .collect_vec()
+    }
+
+    /// The 3^N - 1 points surrounding this one, including diagonals.
+    /// Points which would overflow T are skipped
+    pub fn get_adjacent_points_diagonal(&self) -> Vec<Point<T, N>> {
+        (0..N)
+            .map(|_| -1..=1)
+            .multi_cartesian_product()
+            .filter(|offsets| offsets.iter().any(|&o| o != 0))
+            .filter_map(|offsets| {
+                let offsets: [i8; N] = offsets.try_into().expect("One offset per axis");
+                self.offset(&offsets)
+            })
+            .collect_vec()
+    }
+
+    /// The smallest and largest value on each axis, or None if there are no points
+    pub fn bounding_box<'a, I: IntoIterator<Item = &'a Point<T, N>>>(
+        points: I,
+    ) -> Option<(Point<T, N>, Point<T, N>)>
+    where
+        T: 'a,
+    {
+        points.into_iter().fold(None, |acc, point| {
+            Some(match acc {
+                None => (*point, *point),
+                Some((mut min, mut max)) => {
+                    for axis in 0..N {
+                        min.0[axis] = min.0[axis].min(point.0[axis]);
+                        max.0[axis] = max.0[axis].max(point.0[axis]);
+                    }
+                    (min, max)
+                }
+            })
+        })
+    }
+}
+
+impl<T: PrimInt> Coord3<T> {
+    pub fn x(&self) -> T {
+        self.0[0]
+    }
+
+    pub fn y(&self) -> T {
+        self.0[1]
+    }
+
+    pub fn z(&self) -> T {
+        self.0[2]
+    }
+}
+
+impl<T: PrimInt, const N: usize> Default for Point<T, N> {
+    fn default() -> Self {
+        Point::origin()
+    }
+}
+
+impl<T: PrimInt, const N: usize> From<[T; N]> for Point<T, N> {
+    fn from(values: [T; N]) -> Self {
+        Point(values)
+    }
+}
+
+impl<T: PrimInt> From<(T, T, T)> for Coord3<T> {
+    fn from((x, y, z): (T, T, T)) -> Self {
+        Point([x, y, z])
+    }
+}
+
+impl<T: PrimInt> From<Coordinate<T>> for Point<T, 2> {
+    fn from(c: Coordinate<T>) -> Self {
+        Point([c.row, c.col])
+    }
+}
+
+impl<T: PrimInt, const N: usize> Index<usize> for Point<T, N> {
+    type Output = T;
+
+    fn index(&self, axis: usize) -> &Self::Output {
+        &self.0[axis]
+    }
+}
+
+impl<T: PrimInt, const N: usize> IndexMut<usize> for Point<T, N> {
+    fn index_mut(&mut self, axis: usize) -> &mut Self::Output {
+        &mut self.0[axis]
+    }
+}
+
+impl<T: PrimInt, const N: usize> Add for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn add(mut self, rhs: Self) -> Self::Output {
+        for axis in 0..N {
+            self.0[axis] = self.0[axis] + rhs.0[axis];
+        }
+        self
+    }
+}
+
+impl<T: PrimInt, const N: usize> Sub for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        for axis in 0..N {
+            self.0[axis] = self.0[axis] - rhs.0[axis];
+        }
+        self
+    }
+}
+
+impl<T: PrimInt, const N: usize> Mul<T> for Point<T, N> {
+    type Output = Point<T, N>;
+
+    fn mul(mut self, rhs: T) -> Self::Output {
+        for value in self.0.iter_mut() {
+            *value = *value * rhs;
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn coord3_arithmetic() {
+        let a = Coord3::from((1, 2, 3));
+        let b = Coord3::from((-1, 0, 5));
+        assert_eq!(a + b, Point([0, 2, 8]));
+        assert_eq!(a - b, Point([2, 2, -2]));
+        assert_eq!(b * 3, Point([-3, 0, 15]));
+        assert_eq!((a.x(), a.y(), a.z()), (1, 2, 3));
+    }
+
+    #[test]
+    fn distances() {
+        let a = Point([1u32, 5, 2, 0]);
+        let b = Point([4u32, 1, 2, 1]);
+        assert_eq!(a.manhattan_distance(&b), 8);
+        assert_eq!(a.chebyshev_distance(&b), 4);
+    }
+
+    #[test]
+    fn adjacent_points() {
+        let p = Coord3::from((0i64, 0, 0));
+        assert_eq!(p.get_adjacent_points().len(), 6);
+        assert_eq!(p.get_adjacent_points_diagonal().len(), 26);
+        assert!(p
+            .get_adjacent_points_diagonal()
+            .iter()
+            .all(|n| n.chebyshev_distance(&p) == 1));
+    }
+
+    #[test]
+    fn adjacent_points_unsigned_origin() {
+        let p = Point([0usize, 0]);
+        assert_eq!(p.get_adjacent_points(), vec![Point([1, 0]), Point([0, 1])]);
+        assert_eq!(p.get_adjacent_points_diagonal().len(), 3);
+    }
+
+    #[test]
+    fn bounding_box() {
+        let points = [Point([1, -2, 3]), Point([0, 4, 3]), Point([2, 0, -1])];
+        assert_eq!(
+            Point::bounding_box(&points),
+            Some((Point([0, -2, -1]), Point([2, 4, 3])))
+        );
+        assert_eq!(Point::<i32, 2>::bounding_box(&[]), None);
+    }
+}
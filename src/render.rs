@@ -0,0 +1,284 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+use std::hash::Hash;
+use std::io::{self, Write};
+
+use itertools::Itertools;
+use num::{range_inclusive, PrimInt};
+
+use crate::coordinate::{BoundingBox, Coordinate};
+
+/// Which index rulers to draw alongside a rendered grid
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Rulers {
+    /// Label each row with its index on the left
+    pub rows: bool,
+    /// Label each column with its index above, written vertically so each digit lines up with its column
+    pub cols: bool,
+}
+
+impl Rulers {
+    pub fn both() -> Rulers {
+        Rulers {
+            rows: true,
+            cols: true,
+        }
+    }
+}
+
+// Rulers assume each cell renders as a single character
+pub(crate) fn write_cells<W: Write, C: Display>(
+    writer: &mut W,
+    row_labels: &[String],
+    col_labels: &[String],
+    mut cell: impl FnMut(usize, usize) -> C,
+    rulers: Rulers,
+) -> io::Result<()> {
+    let row_width = row_labels.iter().map(|l| l.len()).max().unwrap_or(0);
+    let prefix = if rulers.rows {
+        " ".repeat(row_width + 1)
+    } else {
+        String::new()
+    };
+    if rulers.cols {
+        let height = col_labels.iter().map(|l| l.len()).max().unwrap_or(0);
+        for line in 0..height {
+            write!(writer, "{}", prefix)?;
+            for label in col_labels {
+                // Right align, so the units are all on the last line
+                let padding = height - label.len();
+                let c = if line < padding {
+                    ' '
+                } else {
+                    label.as_bytes()[line - padding] as char
+                };
+                write!(writer, "{}", c)?;
+            }
+            writeln!(writer)?;
+        }
+    }
+    for (row, label) in row_labels.iter().enumerate() {
+        if rulers.rows {
+            write!(writer, "{:>width$} ", label, width = row_width)?;
+        }
+        for col in 0..col_labels.len() {
+            write!(writer, "{}", cell(row, col))?;
+        }
+        writeln!(writer)?;
+    }
+    Ok(())
+}
+
+pub(crate) fn render(write: impl FnOnce(&mut Vec<u8>) -> io::Result<()>) -> String {
+    let mut buffer = vec![];
+    write(&mut buffer).expect("Writing to a Vec can't fail");
+    String::from_utf8(buffer).expect("Rendered grids are always valid utf-8")
+}
+
+/// Write each row of the matrix on its own line, converting each cell with `cell`
+pub fn write_matrix<W: Write, T, C: Display>(
+    writer: &mut W,
+    matrix: &[Vec<T>],
+    cell: impl Fn(&T) -> C,
+    rulers: Rulers,
+) -> io::Result<()> {
+    let cols = matrix.iter().map(|row| row.len()).max().unwrap_or(0);
+    write_cells(
+        writer,
+        &(0..matrix.len()).map(|i| i.to_string()).collect_vec(),
+        &(0..cols).map(|i| i.to_string()).collect_vec(),
+        // Pad out ragged rows rather than failing
+        |row, col| {
+            matrix[row]
+                .get(col)
+                .map_or_else(|| " ".to_string(), |x| cell(x).to_string())
+        },
+        rulers,
+    )
+}
+
+pub fn render_matrix<T, C: Display>(
+    matrix: &[Vec<T>],
+    cell: impl Fn(&T) -> C,
+    rulers: Rulers,
+) -> String {
+    render(|w| write_matrix(w, matrix, cell, rulers))
+}
+
+// Write every cell in the viewport, or the bounding box of `points` if there isn't one.
+// Cells are looked up individually, so the cost scales with the area rather than area * points
+pub(crate) fn write_sparse<'a, W, T, C, I, F>(
+    writer: &mut W,
+    points: I,
+    lookup: F,
+    viewport: Option<BoundingBox<T>>,
+    origin_top_left: bool,
+    rulers: Rulers,
+) -> io::Result<()>
+where
+    W: Write,
+    T: PrimInt + Display + 'a,
+    C: Display,
+    I: Iterator<Item = &'a Coordinate<T>>,
+    F: Fn(&Coordinate<T>) -> Option<C>,
+{
+    let Some(viewport) = viewport.or_else(|| BoundingBox::from_coordinates(points)) else {
+        return Ok(());
+    };
+    let mut rows = range_inclusive(viewport.min.row, viewport.max.row).collect_vec();
+    if !origin_top_left {
+        rows.reverse();
+    }
+    let cols = range_inclusive(viewport.min.col, viewport.max.col).collect_vec();
+    write_cells(
+        writer,
+        &rows.iter().map(|r| r.to_string()).collect_vec(),
+        &cols.iter().map(|c| c.to_string()).collect_vec(),
+        |row, col| match lookup(&Coordinate::new(rows[row], cols[col])) {
+            Some(marker) => marker.to_string(),
+            None => ".".to_string(),
+        },
+        rulers,
+    )
+}
+
+/// Write the bounding box of the coordinates, with # where a coordinate is present and . where it's absent.
+/// If `origin_top_left` is false, rows increase going up the page
+pub fn write_coordinates<W: Write, T: PrimInt + Display + Hash>(
+    writer: &mut W,
+    coordinates: &[Coordinate<T>],
+    viewport: Option<BoundingBox<T>>,
+    origin_top_left: bool,
+    rulers: Rulers,
+) -> io::Result<()> {
+    let present: HashSet<_> = coordinates.iter().collect();
+    write_sparse(
+        writer,
+        coordinates.iter(),
+        |c| present.contains(c).then_some('#'),
+        viewport,
+        origin_top_left,
+        rulers,
+    )
+}
+
+pub fn render_coordinates<T: PrimInt + Display + Hash>(
+    coordinates: &[Coordinate<T>],
+    viewport: Option<BoundingBox<T>>,
+    origin_top_left: bool,
+    rulers: Rulers,
+) -> String {
+    render(|w| write_coordinates(w, coordinates, viewport, origin_top_left, rulers))
+}
+
+/// As `write_coordinates`, but writing each coordinate's own marker, e.g. a label or a count.
+/// Markers should be a single character wide to keep the columns aligned
+pub fn write_markers<W: Write, T: PrimInt + Display + Hash, C: Display>(
+    writer: &mut W,
+    markers: &HashMap<Coordinate<T>, C>,
+    viewport: Option<BoundingBox<T>>,
+    origin_top_left: bool,
+    rulers: Rulers,
+) -> io::Result<()> {
+    write_sparse(
+        writer,
+        markers.keys(),
+        |c| markers.get(c),
+        viewport,
+        origin_top_left,
+        rulers,
+    )
+}
+
+pub fn render_markers<T: PrimInt + Display + Hash, C: Display>(
+    markers: &HashMap<Coordinate<T>, C>,
+    viewport: Option<BoundingBox<T>>,
+    origin_top_left: bool,
+    rulers: Rulers,
+) -> String {
+    render(|w| write_markers(w, markers, viewport, origin_top_left, rulers))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn render_matrix_custom_cell() {
+        let matrix = vec![vec![true, false, true], vec![false, true, false]];
+        let result = render_matrix(&matrix, |&b| if b { '#' } else { '.' }, Rulers::default());
+        assert_eq!(result, "#.#\n.#.\n");
+    }
+
+    #[test]
+    fn render_matrix_rulers() {
+        let matrix = vec![vec![0; 11]; 2];
+        let result = render_matrix(&matrix, |x| *x, Rulers::both());
+        assert_eq!(
+            result,
+            concat!(
+                "            1\n",
+                "  01234567890\n",
+                "0 00000000000\n",
+                "1 00000000000\n",
+            )
+        );
+    }
+
+    #[test]
+    fn render_coordinates_origin_bottom_left() {
+        let coordinates = vec![Coordinate::new(-1, 0), Coordinate::new(1, 1)];
+        let result = render_coordinates(&coordinates, None, false, Rulers::default());
+        assert_eq!(result, ".#\n..\n#.\n");
+        let result = render_coordinates(
+            &coordinates,
+            None,
+            true,
+            Rulers {
+                rows: true,
+                cols: false,
+            },
+        );
+        assert_eq!(result, "-1 #.\n 0 ..\n 1 .#\n");
+    }
+
+    #[test]
+    fn write_coordinates_to_writer() -> color_eyre::Result<()> {
+        let mut output = vec![];
+        write_coordinates(
+            &mut output,
+            &[Coordinate::new(0u64, 0)],
+            None,
+            true,
+            Rulers::default(),
+        )?;
+        assert_eq!(output, b"#\n");
+        Ok(())
+    }
+
+    #[test]
+    fn render_coordinates_empty() {
+        let coordinates: Vec<Coordinate<i32>> = vec![];
+        assert_eq!(
+            render_coordinates(&coordinates, None, true, Rulers::both()),
+            ""
+        );
+    }
+
+    #[test]
+    fn render_coordinates_viewport() {
+        let coordinates = vec![Coordinate::new(0, 0), Coordinate::new(100, 100)];
+        let viewport = BoundingBox::new(Coordinate::new(-1, -1), Coordinate::new(1, 2));
+        let result = render_coordinates(&coordinates, Some(viewport), true, Rulers::default());
+        assert_eq!(result, "....\n.#..\n....\n");
+    }
+
+    #[test]
+    fn render_markers_counts() {
+        let markers: HashMap<_, _> = vec![(Coordinate::new(0u8, 0), 2), (Coordinate::new(1, 2), 7)]
+            .into_iter()
+            .collect();
+        let result = render_markers(&markers, None, true, Rulers::default());
+        assert_eq!(result, "2..\n..7\n");
+    }
+}
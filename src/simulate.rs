@@ -0,0 +1,162 @@
+use std::hash::Hash;
+
+use crate::cycle;
+
+/// Steps a state through a sequence of instructions, e.g. the directions in 2023 day 8
+#[derive(Clone, Copy, Debug)]
+pub struct Simulation<'a, I> {
+    instructions: &'a [I],
+    repeat: bool,
+    trace: bool,
+}
+
+/// Where a simulation stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Outcome<S> {
+    pub steps: usize,
+    pub state: S,
+    /// Every state from the initial one to `state`, if requested with `with_trace`
+    pub trace: Option<Vec<S>>,
+}
+
+impl<'a, I> Simulation<'a, I> {
+    pub fn new(instructions: &'a [I]) -> Self {
+        Simulation {
+            instructions,
+            repeat: false,
+            trace: false,
+        }
+    }
+
+    /// Start again from the first instruction after the last one, forever
+    pub fn repeating(mut self) -> Self {
+        self.repeat = true;
+        self
+    }
+
+    /// Keep every state, for debugging
+    pub fn with_trace(mut self) -> Self {
+        self.trace = true;
+        self
+    }
+
+    fn instructions(&self) -> impl Iterator<Item = &'a I> {
+        // usize::MAX steps is as good as forever
+        let cycles = if self.repeat { usize::MAX } else { 1 };
+        self.instructions
+            .iter()
+            .cycle()
+            .take(self.instructions.len().saturating_mul(cycles))
+    }
+
+    /// Apply instructions until `done` holds for the new state. `done` isn't checked on the
+    /// initial state. Returns None if the instructions run out first. With repeating
+    /// instructions this only returns if `done` holds eventually
+    pub fn run<S: Clone>(
+        &self,
+        initial: S,
+        mut step: impl FnMut(&S, &I) -> S,
+        mut done: impl FnMut(&S) -> bool,
+    ) -> Option<Outcome<S>> {
+        let mut trace = self.trace.then(|| vec![initial.clone()]);
+        let mut state = initial;
+        for (steps, instruction) in self.instructions().enumerate() {
+            state = step(&state, instruction);
+            if let Some(trace) = trace.as_mut() {
+                trace.push(state.clone());
+            }
+            if done(&state) {
+                return Some(Outcome {
+                    steps: steps + 1,
+                    state,
+                    trace,
+                });
+            }
+        }
+        None
+    }
+
+    /// The state after `n` steps, or None if the instructions run out first.
+    /// With repeating instructions this skips ahead once the state and position in the
+    /// instructions repeat together, so `n` can be far larger than could be simulated
+    pub fn nth<S: Clone + Hash + Eq>(
+        &self,
+        initial: S,
+        mut step: impl FnMut(&S, &I) -> S,
+        n: usize,
+    ) -> Option<S> {
+        let len = self.instructions.len();
+        if n == 0 {
+            return Some(initial);
+        }
+        if len == 0 || (!self.repeat && n > len) {
+            return None;
+        }
+        let (state, _) = cycle::nth_state(
+            (initial, 0),
+            |(state, index)| (step(state, &self.instructions[*index]), (index + 1) % len),
+            n,
+        );
+        Some(state)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(position: &i32, instruction: &char) -> i32 {
+        match instruction {
+            'U' => position + 1,
+            'D' => position - 1,
+            _ => *position,
+        }
+    }
+
+    #[test]
+    fn run_once() {
+        let instructions = ['U', 'U', 'D', 'U'];
+        let simulation = Simulation::new(&instructions);
+        assert_eq!(
+            simulation.run(0, turn, |&p| p == 2),
+            Some(Outcome {
+                steps: 2,
+                state: 2,
+                trace: None
+            })
+        );
+        assert_eq!(simulation.run(0, turn, |&p| p == 3), None);
+        let traced = simulation.with_trace().run(0, turn, |&p| p < 2).unwrap();
+        assert_eq!(traced.trace, Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn run_repeating() {
+        let instructions = ['U', 'U', 'D'];
+        let outcome = Simulation::new(&instructions)
+            .repeating()
+            .run(0, turn, |&p| p == 5)
+            .unwrap();
+        assert_eq!(outcome.steps, 11);
+        assert_eq!(
+            Simulation::new(&[] as &[char])
+                .repeating()
+                .run(0, turn, |_| true),
+            None
+        );
+    }
+
+    #[test]
+    fn skip_ahead() {
+        // Loops around 0..4
+        let instructions = ['U', 'U', 'U', 'D', 'D', 'D', 'U'];
+        let simulation = Simulation::new(&instructions).repeating();
+        let wrap = |p: &i32, i: &char| turn(p, i).rem_euclid(4);
+        assert_eq!(simulation.nth(0, wrap, 0), Some(0));
+        assert_eq!(simulation.nth(0, wrap, 3), Some(3));
+        assert_eq!(simulation.nth(0, wrap, 7 * 1_000_000_000 + 3), Some(3));
+        // Each pass of the instructions moves up one, so it repeats every 28 steps
+        assert_eq!(simulation.nth(0, wrap, usize::MAX), Some(3));
+        assert_eq!(Simulation::new(&instructions).nth(0, wrap, 8), None);
+    }
+}
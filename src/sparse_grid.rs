@@ -0,0 +1,219 @@
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::iter;
+
+use itertools::Itertools;
+
+use crate::coordinate::{BoundingBox, Coordinate};
+use crate::render::{self, Rulers};
+
+// Neighbours of a sparse grid are never clipped
+const UNBOUNDED: BoundingBox<i64> = BoundingBox {
+    min: Coordinate {
+        row: i64::MIN,
+        col: i64::MIN,
+    },
+    max: Coordinate {
+        row: i64::MAX,
+        col: i64::MAX,
+    },
+};
+
+/// A grid which only stores the cells that have been set, so it can grow in any direction.
+/// Mirrors the `Matrix` API, but cells can be missing
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Coordinate<i64>, T>,
+    bounds: Option<BoundingBox<i64>>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: HashMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Take the cells of a dense grid which match `keep`, e.g. only the walls of a maze
+    pub fn from_rows(rows: Vec<Vec<T>>, keep: impl Fn(&T) -> bool) -> SparseGrid<T> {
+        rows.into_iter()
+            .enumerate()
+            .flat_map(|(row, values)| {
+                values
+                    .into_iter()
+                    .enumerate()
+                    .map(move |(col, value)| (Coordinate::new(row as i64, col as i64), value))
+            })
+            .filter(|(_, value)| keep(value))
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The smallest box containing every set cell, or None if the grid is empty
+    pub fn bounds(&self) -> Option<BoundingBox<i64>> {
+        self.bounds
+    }
+
+    pub fn contains(&self, coordinate: Coordinate<i64>) -> bool {
+        self.cells.contains_key(&coordinate)
+    }
+
+    pub fn get(&self, coordinate: Coordinate<i64>) -> Option<&T> {
+        self.cells.get(&coordinate)
+    }
+
+    pub fn get_mut(&mut self, coordinate: Coordinate<i64>) -> Option<&mut T> {
+        self.cells.get_mut(&coordinate)
+    }
+
+    /// Set the value at a coordinate, returning the old value if there was one
+    pub fn set(&mut self, coordinate: Coordinate<i64>, value: T) -> Option<T> {
+        match self.bounds.as_mut() {
+            Some(bounds) => bounds.expand(&coordinate),
+            None => self.bounds = Some(BoundingBox::new(coordinate, coordinate)),
+        }
+        self.cells.insert(coordinate, value)
+    }
+
+    pub fn remove(&mut self, coordinate: Coordinate<i64>) -> Option<T> {
+        let value = self.cells.remove(&coordinate)?;
+        // Only a cell on the edge can shrink the bounds
+        if let Some(bounds) = self.bounds {
+            if coordinate.row == bounds.min.row
+                || coordinate.row == bounds.max.row
+                || coordinate.col == bounds.min.col
+                || coordinate.col == bounds.max.col
+            {
+                self.bounds = BoundingBox::from_coordinates(self.cells.keys());
+            }
+        }
+        Some(value)
+    }
+
+    /// Every set cell, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (&Coordinate<i64>, &T)> {
+        self.cells.iter()
+    }
+
+    /// The set cells out of the 4 orthogonal neighbours of a coordinate
+    pub fn neighbours(&self, coordinate: Coordinate<i64>) -> Vec<(Coordinate<i64>, &T)> {
+        self.with_values(coordinate.get_adjacent_points(&UNBOUNDED))
+    }
+
+    /// The set cells out of the 8 neighbours of a coordinate, including diagonals
+    pub fn neighbours_diagonal(&self, coordinate: Coordinate<i64>) -> Vec<(Coordinate<i64>, &T)> {
+        self.with_values(coordinate.get_adjacent_points_diagonal(&UNBOUNDED))
+    }
+
+    fn with_values(&self, coordinates: Vec<Coordinate<i64>>) -> Vec<(Coordinate<i64>, &T)> {
+        coordinates
+            .into_iter()
+            .filter_map(|c| self.get(c).map(|value| (c, value)))
+            .collect_vec()
+    }
+
+    /// Draw the bounding box with `cell` for set cells and . for missing ones
+    pub fn render<C: Display>(&self, cell: impl Fn(&T) -> C, rulers: Rulers) -> String {
+        render::render(|w| {
+            render::write_sparse(
+                w,
+                iter::empty(),
+                |c| self.get(*c).map(&cell),
+                self.bounds,
+                true,
+                rulers,
+            )
+        })
+    }
+}
+
+impl<T> FromIterator<(Coordinate<i64>, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Coordinate<i64>, T)>>(cells: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (coordinate, value) in cells {
+            grid.set(coordinate, value);
+        }
+        grid
+    }
+}
+
+impl<T: Display> Display for SparseGrid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.render(|x| x.to_string(), Rulers::default()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn grows_in_any_direction() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.set(Coordinate::new(0, 0), 'a'), None);
+        assert_eq!(grid.set(Coordinate::new(-2, 3), 'b'), None);
+        assert_eq!(grid.set(Coordinate::new(0, 0), 'c'), Some('a'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(
+            grid.bounds(),
+            Some(BoundingBox::new(
+                Coordinate::new(-2, 0),
+                Coordinate::new(0, 3)
+            ))
+        );
+        assert_eq!(grid.to_string(), "...b\n....\nc...\n");
+    }
+
+    #[test]
+    fn remove_shrinks_bounds() {
+        let mut grid: SparseGrid<_> = [(0, 0), (1, 1), (5, 5)]
+            .map(|c| (Coordinate::from(c), ()))
+            .into_iter()
+            .collect();
+        assert_eq!(grid.remove(Coordinate::new(5, 5)), Some(()));
+        assert_eq!(grid.remove(Coordinate::new(5, 5)), None);
+        assert_eq!(
+            grid.bounds(),
+            Some(BoundingBox::new(
+                Coordinate::new(0, 0),
+                Coordinate::new(1, 1)
+            ))
+        );
+        grid.remove(Coordinate::new(0, 0));
+        grid.remove(Coordinate::new(1, 1));
+        assert!(grid.is_empty());
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn neighbours_from_rows() {
+        let grid = SparseGrid::from_rows(
+            vec!["#.#".chars().collect(), ".##".chars().collect()],
+            |&c| c == '#',
+        );
+        assert_eq!(grid.len(), 4);
+        assert_eq!(
+            grid.neighbours(Coordinate::new(1, 2)),
+            vec![(Coordinate::new(0, 2), &'#'), (Coordinate::new(1, 1), &'#')]
+        );
+        assert_eq!(grid.neighbours_diagonal(Coordinate::new(0, 1)).len(), 4);
+        assert_eq!(
+            grid.render(|_| '#', Rulers::both()),
+            "  012\n0 #.#\n1 .##\n"
+        );
+    }
+}
@@ -0,0 +1,181 @@
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use itertools::Itertools;
+use num::PrimInt;
+
+use crate::coordinate::{BoundingBox, Coordinate};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Element {
+    Polyline(Vec<Coordinate<i64>>, String),
+    Polygon(Vec<Coordinate<i64>>, String),
+    Point(Coordinate<i64>, String),
+    Label(Coordinate<i64>, String),
+}
+
+/// A vector drawing of coordinates, with columns along x and rows along y.
+/// Elements are drawn in the order they're added, and the output only depends on what was added,
+/// so it's stable enough to compare against a checked in file
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Svg {
+    elements: Vec<Element>,
+}
+
+fn to_i64<T: PrimInt>(c: &Coordinate<T>) -> Coordinate<i64> {
+    Coordinate::new(
+        c.row.to_i64().expect("Row must fit in an i64"),
+        c.col.to_i64().expect("Column must fit in an i64"),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn format_points(points: &[Coordinate<i64>]) -> String {
+    points
+        .iter()
+        .map(|c| format!("{},{}", c.col, c.row))
+        .join(" ")
+}
+
+impl Svg {
+    pub fn new() -> Svg {
+        Svg::default()
+    }
+
+    /// An open path through each coordinate in order
+    pub fn polyline<T: PrimInt>(&mut self, points: &[Coordinate<T>], colour: &str) -> &mut Self {
+        self.elements.push(Element::Polyline(
+            points.iter().map(to_i64).collect(),
+            colour.to_string(),
+        ));
+        self
+    }
+
+    /// A closed, filled shape with the coordinates as its corners
+    pub fn polygon<T: PrimInt>(&mut self, points: &[Coordinate<T>], colour: &str) -> &mut Self {
+        self.elements.push(Element::Polygon(
+            points.iter().map(to_i64).collect(),
+            colour.to_string(),
+        ));
+        self
+    }
+
+    pub fn points<T: PrimInt>(&mut self, points: &[Coordinate<T>], colour: &str) -> &mut Self {
+        self.elements.extend(
+            points
+                .iter()
+                .map(|c| Element::Point(to_i64(c), colour.to_string())),
+        );
+        self
+    }
+
+    pub fn label<T: PrimInt>(&mut self, at: &Coordinate<T>, text: &str) -> &mut Self {
+        self.elements
+            .push(Element::Label(to_i64(at), text.to_string()));
+        self
+    }
+
+    fn coordinates(&self) -> impl Iterator<Item = &Coordinate<i64>> {
+        self.elements.iter().flat_map(|element| match element {
+            Element::Polyline(points, _) | Element::Polygon(points, _) => {
+                points.iter().collect_vec()
+            }
+            Element::Point(at, _) | Element::Label(at, _) => vec![at],
+        })
+    }
+
+    pub fn write<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "{}", self)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+}
+
+impl Display for Svg {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let bounds = BoundingBox::from_coordinates(self.coordinates())
+            .unwrap_or_else(|| BoundingBox::new(Coordinate::new(0, 0), Coordinate::new(0, 0)));
+        // Leave a cell of margin so points on the edge aren't clipped
+        writeln!(
+            f,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {} {}">"#,
+            bounds.min.col - 1,
+            bounds.min.row - 1,
+            bounds.width() + 1,
+            bounds.height() + 1
+        )?;
+        for element in &self.elements {
+            match element {
+                Element::Polyline(points, colour) => writeln!(
+                    f,
+                    r#"  <polyline points="{}" fill="none" stroke="{}" stroke-width="0.1"/>"#,
+                    format_points(points),
+                    escape(colour)
+                )?,
+                Element::Polygon(points, colour) => writeln!(
+                    f,
+                    r#"  <polygon points="{}" fill="{}" fill-opacity="0.5" stroke="{}" stroke-width="0.1"/>"#,
+                    format_points(points),
+                    escape(colour),
+                    escape(colour)
+                )?,
+                Element::Point(at, colour) => writeln!(
+                    f,
+                    r#"  <circle cx="{}" cy="{}" r="0.25" fill="{}"/>"#,
+                    at.col,
+                    at.row,
+                    escape(colour)
+                )?,
+                Element::Label(at, text) => writeln!(
+                    f,
+                    r#"  <text x="{}" y="{}" font-size="0.5">{}</text>"#,
+                    at.col,
+                    at.row,
+                    escape(text)
+                )?,
+            }
+        }
+        writeln!(f, "</svg>")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn golden_file() {
+        let square = [(0, 0), (0, 2), (2, 2), (2, 0)].map(Coordinate::from);
+        let mut svg = Svg::new();
+        svg.polygon(&square, "green")
+            .polyline(
+                &[
+                    Coordinate::new(-1, 1),
+                    Coordinate::new(1, 1),
+                    Coordinate::new(1, 3),
+                ],
+                "red",
+            )
+            .points(&square, "black")
+            .label(&Coordinate::new(3, 0), "a < b & c");
+        assert_eq!(svg.to_string(), include_str!("../input/svg.test.svg"));
+    }
+
+    #[test]
+    fn empty() {
+        assert_eq!(
+            Svg::new().to_string(),
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"-1 -1 2 2\">\n</svg>\n"
+        );
+    }
+}
@@ -0,0 +1,258 @@
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+use num::PrimInt;
+
+use crate::coordinate::{BoundingBox, Coordinate};
+
+pub type Colour = [u8; 3];
+
+pub const BLACK: Colour = [0, 0, 0];
+pub const WHITE: Colour = [255, 255, 255];
+pub const RED: Colour = [255, 0, 0];
+
+/// An RGB image with one pixel per grid cell, for debugging grid puzzles visually
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    // The coordinate drawn in the top left pixel
+    origin: Coordinate<i64>,
+    pixels: Vec<Colour>,
+}
+
+impl Image {
+    /// Draw a grid, choosing each cell's colour with `colour`. Ragged rows are padded with black
+    pub fn from_grid<T>(grid: &[Vec<T>], colour: impl Fn(&T) -> Colour) -> Image {
+        let width = grid.iter().map(|row| row.len()).max().unwrap_or(0);
+        let pixels = grid
+            .iter()
+            .flat_map(|row| (0..width).map(|col| row.get(col).map_or(BLACK, &colour)))
+            .collect_vec();
+        Image {
+            width,
+            height: grid.len(),
+            origin: Coordinate::new(0, 0),
+            pixels,
+        }
+    }
+
+    /// Draw the bounding box of a set of coordinates, with `present` where a coordinate is in the set
+    pub fn from_coordinates<T: PrimInt>(
+        coordinates: &[Coordinate<T>],
+        present: Colour,
+        absent: Colour,
+    ) -> Image {
+        let points = coordinates.iter().filter_map(to_i64).collect_vec();
+        // An empty set of coordinates gives an empty image
+        let bounds = BoundingBox::from_coordinates(&points)
+            .unwrap_or_else(|| BoundingBox::new(Coordinate::new(0, 0), Coordinate::new(-1, -1)));
+        let mut image = Image {
+            width: bounds.width() as usize,
+            height: bounds.height() as usize,
+            origin: bounds.min,
+            pixels: vec![],
+        };
+        image.pixels = vec![absent; image.width * image.height];
+        image.overlay(coordinates, present);
+        image
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn index<T: PrimInt>(&self, coordinate: &Coordinate<T>) -> Option<usize> {
+        let c = to_i64(coordinate)? - self.origin;
+        if (0..self.height as i64).contains(&c.row) && (0..self.width as i64).contains(&c.col) {
+            Some(c.row as usize * self.width + c.col as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get<T: PrimInt>(&self, coordinate: &Coordinate<T>) -> Option<Colour> {
+        self.index(coordinate).map(|i| self.pixels[i])
+    }
+
+    /// Colour in each coordinate, e.g. the path taken by a search. Coordinates outside the image are ignored
+    pub fn overlay<T: PrimInt>(&mut self, coordinates: &[Coordinate<T>], colour: Colour) {
+        for c in coordinates {
+            if let Some(i) = self.index(c) {
+                self.pixels[i] = colour;
+            }
+        }
+    }
+
+    /// Enlarge each cell to a `factor` x `factor` square, since one pixel per cell is hard to see.
+    /// The result is addressed by pixel, with (0, 0) in the top left, so apply any overlays
+    /// before scaling
+    pub fn scale(&self, factor: usize) -> Image {
+        let width = self.width * factor;
+        let pixels = (0..self.height * factor)
+            .flat_map(|y| (0..width).map(move |x| (y / factor) * self.width + x / factor))
+            .map(|i| self.pixels[i])
+            .collect_vec();
+        Image {
+            width,
+            height: self.height * factor,
+            origin: Coordinate::new(0, 0),
+            pixels,
+        }
+    }
+
+    /// Write as a binary PPM, which most image viewers can open
+    pub fn write_ppm<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        write!(writer, "P6\n{} {}\n255\n", self.width, self.height)?;
+        writer.write_all(&self.pixels.concat())
+    }
+
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_ppm(&mut writer)?;
+        writer.flush()
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut w| w.write_image_data(&self.pixels.concat()))
+            .map_err(io::Error::other)
+    }
+
+    #[cfg(feature = "png")]
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        self.write_png(BufWriter::new(File::create(path)?))
+    }
+}
+
+fn to_i64<T: PrimInt>(c: &Coordinate<T>) -> Option<Coordinate<i64>> {
+    Some(Coordinate::new(c.row.to_i64()?, c.col.to_i64()?))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Format {
+    Ppm,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+/// Writes numbered images into a directory, to be stitched into an animation of e.g. a search.
+/// With ffmpeg: `ffmpeg -i frame_%05d.png out.gif`
+#[derive(Debug)]
+pub struct FrameWriter {
+    directory: PathBuf,
+    format: Format,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new<P: AsRef<Path>>(directory: P, format: Format) -> io::Result<FrameWriter> {
+        fs::create_dir_all(&directory)?;
+        Ok(FrameWriter {
+            directory: directory.as_ref().to_path_buf(),
+            format,
+            next: 0,
+        })
+    }
+
+    /// Write the next frame, returning the path it was written to
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let extension = match self.format {
+            Format::Ppm => "ppm",
+            #[cfg(feature = "png")]
+            Format::Png => "png",
+        };
+        let path = self
+            .directory
+            .join(format!("frame_{:05}.{}", self.next, extension));
+        match self.format {
+            Format::Ppm => image.save_ppm(&path)?,
+            #[cfg(feature = "png")]
+            Format::Png => image.save_png(&path)?,
+        }
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_grid_ppm() -> color_eyre::Result<()> {
+        let image = Image::from_grid(&[vec![true, false]], |&b| if b { WHITE } else { BLACK });
+        let mut output = vec![];
+        image.write_ppm(&mut output)?;
+        assert_eq!(output, b"P6\n2 1\n255\n\xff\xff\xff\x00\x00\x00");
+        Ok(())
+    }
+
+    #[test]
+    fn from_coordinates_with_path() {
+        let coordinates = vec![Coordinate::new(-1, -1), Coordinate::new(1, 2)];
+        let mut image = Image::from_coordinates(&coordinates, WHITE, BLACK);
+        assert_eq!((image.width(), image.height()), (4, 3));
+        image.overlay(&[Coordinate::new(0, 0), Coordinate::new(5, 5)], RED);
+        assert_eq!(image.get(&Coordinate::new(-1, -1)), Some(WHITE));
+        assert_eq!(image.get(&Coordinate::new(0, 0)), Some(RED));
+        assert_eq!(image.get(&Coordinate::new(0, 1)), Some(BLACK));
+        assert_eq!(image.get(&Coordinate::new(5, 5)), None);
+    }
+
+    #[test]
+    fn from_coordinates_empty() {
+        let image = Image::from_coordinates::<i32>(&[], WHITE, BLACK);
+        assert_eq!((image.width(), image.height()), (0, 0));
+    }
+
+    #[test]
+    fn scale_up() {
+        let image = Image::from_grid(&[vec![WHITE, BLACK]], |&c| c).scale(2);
+        assert_eq!((image.width(), image.height()), (4, 2));
+        assert_eq!(image.get(&Coordinate::new(1, 1)), Some(WHITE));
+        assert_eq!(image.get(&Coordinate::new(1, 2)), Some(BLACK));
+    }
+
+    #[test]
+    fn scale_resets_origin() {
+        let image = Image::from_coordinates(&[Coordinate::new(-2, -2)], WHITE, BLACK).scale(3);
+        assert_eq!(image.get(&Coordinate::new(0, 0)), Some(WHITE));
+        assert_eq!(image.get(&Coordinate::new(2, 2)), Some(WHITE));
+        assert_eq!(image.get(&Coordinate::new(-2, -2)), None);
+    }
+
+    #[test]
+    fn frame_sequence() -> color_eyre::Result<()> {
+        // Unique per process, so concurrent test runs don't collide
+        let directory =
+            std::env::temp_dir().join(format!("aoc2023_frame_sequence_{}", std::process::id()));
+        let mut frames = FrameWriter::new(&directory, Format::Ppm)?;
+        let image = Image::from_grid(&[vec![WHITE]], |&c| c);
+        assert_eq!(frames.write(&image)?, directory.join("frame_00000.ppm"));
+        assert_eq!(frames.write(&image)?, directory.join("frame_00001.ppm"));
+        fs::remove_dir_all(directory)?;
+        Ok(())
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png_signature() -> color_eyre::Result<()> {
+        let image = Image::from_grid(&[vec![WHITE, RED]], |&c| c);
+        let mut output = vec![];
+        image.write_png(&mut output)?;
+        assert_eq!(&output[..8], b"\x89PNG\r\n\x1a\n");
+        Ok(())
+    }
+}